@@ -1,4 +1,13 @@
-use crate::plugin::{Plugin, PluginDiscovery, PluginService, Scope, ScopeFilter};
+use crate::plugin::{
+    check_stdio_server, find_collisions, parse_duration, BisectState, BisectVerdict, ChangePlan,
+    Changelog, Collision, ContextCost, ExpiringEnable, MarketplaceSummary, McpApproval,
+    McpCheckReport, McpServerEntry, PendingMarketplace, Plugin, PluginDiscovery, PluginService,
+    PluginUpdate, Scope, ScopeFilter,
+};
+use std::time::Duration;
+
+/// Shorter than the CLI default: the TUI waits on the check
+const MCP_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
@@ -7,11 +16,53 @@ pub enum AppMode {
     Help,
     Confirm(ConfirmAction),
     DetailModal,
+    EnableFor, // Typing a duration for a time-limited enable
+    Bisect,    // Bisect wizard
+    MarketplaceModal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tab {
+    #[default]
+    Plugins,
+    McpServers,
+    Marketplaces,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 3] = [Tab::Plugins, Tab::McpServers, Tab::Marketplaces];
+
+    pub fn next(self) -> Self {
+        match self {
+            Tab::Plugins => Tab::McpServers,
+            Tab::McpServers => Tab::Marketplaces,
+            Tab::Marketplaces => Tab::Plugins,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tab::Plugins => "Plugins",
+            Tab::McpServers => "MCP Servers",
+            Tab::Marketplaces => "Marketplaces",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfirmAction {
     Remove,
+    ApplyPlan,
+    EnableWithHooks,
+}
+
+/// An enabled-state change waiting for the user to confirm its diff
+#[derive(Debug, Clone)]
+pub struct PendingChange {
+    pub plan: ChangePlan,
+    pub plugin_id: String,
+    pub scope: Scope,
+    pub enabled: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +97,21 @@ pub struct App {
     pub message: Option<StatusMessage>,
     pub should_quit: bool,
     pub service: PluginService,
+    pub pending_change: Option<PendingChange>,
+    pub collisions: Vec<Collision>, // Name clashes among plugins active in this project
+    pub mcp_checks: Option<(String, Vec<McpCheckReport>)>, // Last MCP check, by plugin id
+    pub tab: Tab,
+    pub mcp_servers: Vec<McpServerEntry>, // Everything Claude would load in this project
+    pub mcp_selected: usize,
+    pub expiring: Vec<ExpiringEnable>, // Running `enable --for` timers
+    pub duration_input: String,
+    enable_for: Option<chrono::Duration>, // Set while a time-limited enable is in progress
+    pub bisect: Option<BisectState>,      // Shared with `ccpm bisect`
+    pub updates: Vec<PluginUpdate>,       // Against the local marketplace checkouts
+    pub changelog: Option<(String, Result<Changelog, String>)>, // Loaded with the detail modal
+    pub marketplaces: Vec<MarketplaceSummary>,
+    pub pending_marketplaces: Vec<PendingMarketplace>, // Declared by the project, listed after
+    pub marketplace_selected: usize,
 }
 
 impl App {
@@ -53,6 +119,22 @@ impl App {
         let discovery = PluginDiscovery::new()?;
         let plugins = discovery.discover_all()?;
         let filtered_plugins: Vec<usize> = (0..plugins.len()).collect();
+        let collisions = find_collisions(&plugins);
+        let mcp_servers = discovery.discover_mcp_servers(&plugins);
+        let service = PluginService::new()?;
+        let expiring = service.load_expiring().expiring;
+        let bisect = service.load_bisect();
+        let updates = discovery.check_updates();
+        let marketplaces = discovery.marketplace_summaries();
+        let pending_marketplaces = discovery.pending_marketplaces();
+        let message = match pending_marketplaces.len() {
+            0 => None,
+            count => Some(StatusMessage::info(format!(
+                "This project declares {} marketplace{} not added yet; see the Marketplaces tab",
+                count,
+                if count == 1 { "" } else { "s" }
+            ))),
+        };
 
         Ok(Self {
             plugins,
@@ -61,19 +143,391 @@ impl App {
             scope_filter: ScopeFilter::All,
             search_query: String::new(),
             mode: AppMode::Normal,
-            message: None,
+            message,
             should_quit: false,
-            service: PluginService::new()?,
+            service,
+            pending_change: None,
+            collisions,
+            mcp_checks: None,
+            tab: Tab::Plugins,
+            mcp_servers,
+            mcp_selected: 0,
+            expiring,
+            duration_input: String::new(),
+            enable_for: None,
+            bisect,
+            updates,
+            changelog: None,
+            marketplaces,
+            pending_marketplaces,
+            marketplace_selected: 0,
         })
     }
 
     pub fn reload_plugins(&mut self) -> color_eyre::Result<()> {
         let discovery = PluginDiscovery::new()?;
         self.plugins = discovery.discover_all()?;
+        self.collisions = find_collisions(&self.plugins);
         self.apply_filter();
+        self.reload_mcp_servers(&discovery);
+        self.expiring = self.service.load_expiring().expiring;
+        self.bisect = self.service.load_bisect();
+        self.updates = discovery.check_updates();
+        self.reload_marketplaces(&discovery);
         Ok(())
     }
 
+    /// Open the bisect wizard, starting a bisect of the plugins active here
+    /// unless one is already running
+    pub fn open_bisect(&mut self) {
+        if self.bisect.is_none() {
+            let ids: Vec<String> = self
+                .plugins
+                .iter()
+                .filter(|p| p.is_active() && !p.is_dev)
+                .map(|p| p.id.clone())
+                .collect();
+            if ids.len() < 2 {
+                self.message = Some(StatusMessage::error(
+                    "Bisect needs at least two plugins enabled in this project",
+                ));
+                return;
+            }
+            match self.service.start_bisect(ids) {
+                Ok(_) => self.after_bisect_change(),
+                Err(e) => {
+                    self.message = Some(StatusMessage::error(format!(
+                        "Failed to start bisect: {}",
+                        e
+                    )));
+                    return;
+                }
+            }
+        }
+        self.mode = AppMode::Bisect;
+    }
+
+    pub fn close_bisect(&mut self) {
+        self.mode = AppMode::Normal;
+    }
+
+    /// Record the result of the current step; selects the culprit once found
+    pub fn mark_bisect(&mut self, verdict: BisectVerdict) {
+        match self.service.mark_bisect(verdict) {
+            Ok(state) => {
+                self.after_bisect_change();
+                if !state.is_done() {
+                    return;
+                }
+                self.mode = AppMode::Normal;
+                let Some(culprit) = state.culprit() else {
+                    self.message = Some(StatusMessage::info(format!(
+                        "Bisect found no culprit after {} steps; local settings restored",
+                        state.steps
+                    )));
+                    return;
+                };
+                self.clear_search();
+                if let Some(index) = self
+                    .filtered_plugins
+                    .iter()
+                    .position(|&i| self.plugins[i].id == culprit)
+                {
+                    self.selected_index = index;
+                }
+                self.message = Some(StatusMessage::info(format!(
+                    "Bisect found {} after {} steps; local settings restored",
+                    culprit, state.steps
+                )));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!("Bisect failed: {}", e)));
+            }
+        }
+    }
+
+    pub fn reset_bisect(&mut self) {
+        match self.service.reset_bisect() {
+            Ok(_) => {
+                self.after_bisect_change();
+                self.mode = AppMode::Normal;
+                self.message = Some(StatusMessage::info("Bisect reset; local settings restored"));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!("Bisect failed: {}", e)));
+            }
+        }
+    }
+
+    fn after_bisect_change(&mut self) {
+        if let Err(e) = self.reload_plugins() {
+            self.message = Some(StatusMessage::error(format!("Reload failed: {}", e)));
+        }
+    }
+
+    /// Update status of an installed plugin
+    pub fn update_for(&self, plugin_id: &str) -> Option<&PluginUpdate> {
+        self.updates.iter().find(|u| u.plugin_id == plugin_id)
+    }
+
+    /// Earliest running `enable --for` timer for a plugin
+    pub fn expiry_for(&self, plugin_id: &str) -> Option<&ExpiringEnable> {
+        self.expiring
+            .iter()
+            .filter(|e| e.plugin_id == plugin_id)
+            .min_by_key(|e| e.expires_at)
+    }
+
+    /// Called about once a second: reverts time-limited enables that ran out
+    /// while the TUI is open
+    pub fn tick(&mut self) {
+        let now = chrono::Utc::now();
+        if !self.expiring.iter().any(|e| e.is_expired(now)) {
+            return;
+        }
+        match self.service.revert_expired() {
+            Ok(expired) => {
+                let _ = self.reload_plugins();
+                let ids: Vec<_> = expired.iter().map(|e| e.plugin_id.as_str()).collect();
+                self.message = Some(StatusMessage::info(format!(
+                    "Time-limited enable expired: {}",
+                    ids.join(", ")
+                )));
+            }
+            Err(e) => {
+                // Stop retrying every tick; a reload picks the entries up again
+                self.expiring.retain(|entry| !entry.is_expired(now));
+                self.message = Some(StatusMessage::error(format!(
+                    "Failed to revert expired enable: {}",
+                    e
+                )));
+            }
+        }
+    }
+
+    /// Ask for a duration, then enable the selected plugin for that long
+    pub fn start_enable_for(&mut self) {
+        if self.reject_dev_toggle() || self.selected_plugin().is_none() {
+            return;
+        }
+        self.duration_input = "2h".to_string();
+        self.mode = AppMode::EnableFor;
+    }
+
+    pub fn append_duration_char(&mut self, c: char) {
+        self.duration_input.push(c);
+    }
+
+    pub fn delete_duration_char(&mut self) {
+        self.duration_input.pop();
+    }
+
+    pub fn cancel_enable_for(&mut self) {
+        self.duration_input.clear();
+        self.mode = AppMode::Normal;
+    }
+
+    pub fn submit_enable_for(&mut self) {
+        let Some(duration) = parse_duration(&self.duration_input) else {
+            self.message = Some(StatusMessage::error(format!(
+                "Invalid duration '{}' (try 45m, 2h or 1d)",
+                self.duration_input
+            )));
+            return;
+        };
+        self.mode = AppMode::Normal;
+        self.duration_input.clear();
+
+        // Hooks still ask for confirmation; the duration waits in `enable_for`
+        self.enable_for = Some(duration);
+        if let Some(plugin) = self.selected_plugin() {
+            if !plugin.contents.hooks.is_empty() {
+                self.mode = AppMode::Confirm(ConfirmAction::EnableWithHooks);
+                return;
+            }
+        }
+        self.change_selected_plugin(true);
+    }
+
+    fn reload_mcp_servers(&mut self, discovery: &PluginDiscovery) {
+        self.mcp_servers = discovery.discover_mcp_servers(&self.plugins);
+        if self.mcp_selected >= self.mcp_servers.len() {
+            self.mcp_selected = self.mcp_servers.len().saturating_sub(1);
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.tab = self.tab.next();
+    }
+
+    pub fn selected_mcp_server(&self) -> Option<&McpServerEntry> {
+        self.mcp_servers.get(self.mcp_selected)
+    }
+
+    pub fn move_mcp_selection(&mut self, delta: i32) {
+        if self.mcp_servers.is_empty() {
+            return;
+        }
+
+        let len = self.mcp_servers.len() as i32;
+        self.mcp_selected = (self.mcp_selected as i32 + delta).rem_euclid(len) as usize;
+    }
+
+    /// Approve a pending or denied project `.mcp.json` server, or deny an approved one.
+    /// Written to the local settings, like Claude Code's own startup prompt.
+    pub fn toggle_selected_mcp_approval(&mut self) {
+        let Some(entry) = self.selected_mcp_server() else {
+            return;
+        };
+        if !entry.needs_approval() {
+            self.message = Some(StatusMessage::info(format!(
+                "{} is a {} server; only project .mcp.json servers need approval",
+                entry.name,
+                entry.origin.label()
+            )));
+            return;
+        }
+
+        let name = entry.name.clone();
+        let approve = !matches!(entry.approval, McpApproval::Approved(_));
+        match self.service.set_mcp_approval(&name, approve, Scope::Local) {
+            Ok(()) => {
+                if let Ok(discovery) = PluginDiscovery::new() {
+                    self.reload_mcp_servers(&discovery);
+                }
+                self.message = Some(StatusMessage::info(format!(
+                    "{} {} in local scope",
+                    name,
+                    if approve { "approved" } else { "denied" }
+                )));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!(
+                    "Failed to update approval: {}",
+                    e
+                )));
+            }
+        }
+    }
+
+    fn reload_marketplaces(&mut self, discovery: &PluginDiscovery) {
+        self.marketplaces = discovery.marketplace_summaries();
+        self.pending_marketplaces = discovery.pending_marketplaces();
+        let len = self.marketplaces.len() + self.pending_marketplaces.len();
+        if self.marketplace_selected >= len {
+            self.marketplace_selected = len.saturating_sub(1);
+        }
+    }
+
+    pub fn selected_marketplace(&self) -> Option<&MarketplaceSummary> {
+        self.marketplaces.get(self.marketplace_selected)
+    }
+
+    /// The selection when it is on a pending marketplace, below the registered ones
+    pub fn selected_pending_marketplace(&self) -> Option<&PendingMarketplace> {
+        self.marketplace_selected
+            .checked_sub(self.marketplaces.len())
+            .and_then(|i| self.pending_marketplaces.get(i))
+    }
+
+    pub fn move_marketplace_selection(&mut self, delta: i32) {
+        let len = self.marketplaces.len() + self.pending_marketplaces.len();
+        if len == 0 {
+            return;
+        }
+
+        let len = len as i32;
+        self.marketplace_selected =
+            (self.marketplace_selected as i32 + delta).rem_euclid(len) as usize;
+    }
+
+    /// Flip the selected marketplace's auto-update flag in known_marketplaces.json
+    pub fn toggle_selected_auto_update(&mut self) {
+        if self.reject_pending_marketplace() {
+            return;
+        }
+        let Some(name) = self.selected_marketplace().map(|m| m.name.clone()) else {
+            return;
+        };
+        match self.service.toggle_auto_update(&name) {
+            Ok(enabled) => {
+                if let Ok(discovery) = PluginDiscovery::new() {
+                    self.reload_marketplaces(&discovery);
+                }
+                self.message = Some(StatusMessage::info(format!(
+                    "Auto-update {} for {}",
+                    if enabled { "on" } else { "off" },
+                    name
+                )));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!(
+                    "Failed to toggle auto-update: {}",
+                    e
+                )));
+            }
+        }
+    }
+
+    /// Register the selected pending marketplace, cloning it if it is a git source.
+    /// Blocks until the clone finishes.
+    pub fn add_selected_pending_marketplace(&mut self) {
+        let Some(pending) = self.selected_pending_marketplace().cloned() else {
+            self.message = Some(StatusMessage::info(
+                "Only marketplaces declared by this project and not added yet can be added here",
+            ));
+            return;
+        };
+        match self
+            .service
+            .register_marketplace(pending.source, Some(&pending.name))
+        {
+            Ok(name) => {
+                if let Ok(discovery) = PluginDiscovery::new() {
+                    self.reload_marketplaces(&discovery);
+                }
+                if let Some(index) = self.marketplaces.iter().position(|m| m.name == name) {
+                    self.marketplace_selected = index;
+                }
+                self.message = Some(StatusMessage::info(format!(
+                    "Added marketplace {}; install its plugins from Claude Code with /plugin",
+                    name
+                )));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!(
+                    "Failed to add {}: {}",
+                    pending.name, e
+                )));
+            }
+        }
+    }
+
+    /// Pending marketplaces have no entry to change until they are added
+    fn reject_pending_marketplace(&mut self) -> bool {
+        let Some(pending) = self.selected_pending_marketplace() else {
+            return false;
+        };
+        self.message = Some(StatusMessage::info(format!(
+            "{} is not added yet; press a to add it",
+            pending.name
+        )));
+        true
+    }
+
+    pub fn show_marketplace_modal(&mut self) {
+        if self.reject_pending_marketplace() {
+            return;
+        }
+        if self.selected_marketplace().is_some() {
+            self.mode = AppMode::MarketplaceModal;
+        }
+    }
+
+    pub fn hide_marketplace_modal(&mut self) {
+        self.mode = AppMode::Normal;
+    }
+
     pub fn selected_plugin(&self) -> Option<&Plugin> {
         self.filtered_plugins
             .get(self.selected_index)
@@ -140,12 +594,7 @@ impl App {
             .enumerate()
             .filter(|(_, p)| {
                 // Scope filter (based on installation scope)
-                let scope_match = match self.scope_filter {
-                    ScopeFilter::All => true,
-                    ScopeFilter::User => p.install_scope == Scope::User,
-                    ScopeFilter::Project => p.install_scope == Scope::Project,
-                    ScopeFilter::Local => p.install_scope == Scope::Local,
-                };
+                let scope_match = self.scope_filter.matches(p);
 
                 // Search filter
                 let search_match = query_lower.is_empty()
@@ -167,91 +616,209 @@ impl App {
         }
     }
 
+    /// Validation errors across all development plugins, shown after a reload
+    pub fn dev_error_count(&self) -> usize {
+        self.plugins.iter().map(|p| p.error_count()).sum()
+    }
+
+    /// Dev plugins are loaded with `claude --plugin-dir`, not through settings
+    fn reject_dev_toggle(&mut self) -> bool {
+        let Some(plugin) = self.selected_plugin() else {
+            return false;
+        };
+        if !plugin.is_dev {
+            return false;
+        }
+        let message = format!(
+            "{} is a dev plugin; load it with claude --plugin-dir {}",
+            plugin.name,
+            plugin
+                .install_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        );
+        self.message = Some(StatusMessage::info(message));
+        true
+    }
+
     pub fn toggle_selected_plugin(&mut self) {
+        if self.reject_dev_toggle() {
+            return;
+        }
         if let Some(plugin) = self.selected_plugin() {
-            let id = plugin.id.clone();
-            let scope = plugin.install_scope;
-
-            match self.service.toggle_plugin(plugin) {
-                Ok(new_state) => {
-                    if let Some(p) = self.plugins.iter_mut().find(|p| p.id == id) {
-                        // Update the appropriate enabled field based on scope
-                        match scope {
-                            Scope::User => p.enabled_user = Some(new_state),
-                            Scope::Project => p.enabled_project = Some(new_state),
-                            Scope::Local => p.enabled_local = Some(new_state),
-                        }
-                    }
-                    self.message = Some(StatusMessage::info(format!(
-                        "{} {} in {} scope",
-                        id,
-                        if new_state { "enabled" } else { "disabled" },
-                        scope
-                    )));
-                }
-                Err(e) => {
-                    self.message = Some(StatusMessage::error(format!("Failed to toggle: {}", e)));
-                }
+            if plugin.is_enabled() {
+                self.change_selected_plugin(false);
+            } else {
+                self.enable_selected_plugin();
             }
         }
     }
 
+    /// Enabling a plugin that ships hooks asks for an extra confirmation first,
+    /// since hooks run shell commands on every matching event
     pub fn enable_selected_plugin(&mut self) {
+        if self.reject_dev_toggle() {
+            return;
+        }
         if let Some(plugin) = self.selected_plugin() {
             if plugin.is_enabled() {
                 self.message = Some(StatusMessage::info("Plugin already enabled"));
                 return;
             }
-
-            let id = plugin.id.clone();
-            let scope = plugin.install_scope;
-
-            match self.service.enable_plugin(&id, scope) {
-                Ok(()) => {
-                    if let Some(p) = self.plugins.iter_mut().find(|p| p.id == id) {
-                        match scope {
-                            Scope::User => p.enabled_user = Some(true),
-                            Scope::Project => p.enabled_project = Some(true),
-                            Scope::Local => p.enabled_local = Some(true),
-                        }
-                    }
-                    self.message = Some(StatusMessage::info(format!("Enabled {}", id)));
-                }
-                Err(e) => {
-                    self.message = Some(StatusMessage::error(format!("Failed to enable: {}", e)));
-                }
+            if !plugin.contents.hooks.is_empty() {
+                self.mode = AppMode::Confirm(ConfirmAction::EnableWithHooks);
+                return;
             }
+            self.change_selected_plugin(true);
         }
     }
 
     pub fn disable_selected_plugin(&mut self) {
+        if self.reject_dev_toggle() {
+            return;
+        }
         if let Some(plugin) = self.selected_plugin() {
             if !plugin.is_enabled() {
                 self.message = Some(StatusMessage::info("Plugin already disabled"));
                 return;
             }
+            self.change_selected_plugin(false);
+        }
+    }
+
+    /// Write the selected plugin's enabled state in its install scope.
+    /// Plugins installed in another project are written to that project's settings,
+    /// so the diff is shown in a confirm dialog before anything is applied.
+    fn change_selected_plugin(&mut self, enabled: bool) {
+        let Some(plugin) = self.selected_plugin() else {
+            return;
+        };
+        let id = plugin.id.clone();
+        let scope = plugin.install_scope;
+        let other_project = match (scope, plugin.is_current_project) {
+            (Scope::Project | Scope::Local, false) => plugin.project_path.clone(),
+            _ => None,
+        };
+
+        let planned = match self.enable_for.take().filter(|_| enabled) {
+            Some(duration) => self.service.plan_enable_until(
+                &id,
+                scope,
+                other_project.as_deref(),
+                chrono::Utc::now() + duration,
+            ),
+            None => self
+                .service
+                .plan_set_enabled(&id, scope, other_project.as_deref(), enabled),
+        };
+        let plan = match planned {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!(
+                    "Failed to plan change: {}",
+                    e
+                )));
+                return;
+            }
+        };
+
+        let pending = PendingChange {
+            plan,
+            plugin_id: id,
+            scope,
+            enabled,
+        };
+
+        if other_project.is_some() {
+            self.pending_change = Some(pending);
+            self.mode = AppMode::Confirm(ConfirmAction::ApplyPlan);
+        } else {
+            self.apply_change(pending);
+        }
+    }
 
-            let id = plugin.id.clone();
-            let scope = plugin.install_scope;
-
-            match self.service.disable_plugin(&id, scope) {
-                Ok(()) => {
-                    if let Some(p) = self.plugins.iter_mut().find(|p| p.id == id) {
-                        match scope {
-                            Scope::User => p.enabled_user = Some(false),
-                            Scope::Project => p.enabled_project = Some(false),
-                            Scope::Local => p.enabled_local = Some(false),
-                        }
+    fn apply_change(&mut self, change: PendingChange) {
+        match self.service.apply(&change.plan) {
+            Ok(()) => {
+                if let Some(p) = self.plugins.iter_mut().find(|p| p.id == change.plugin_id) {
+                    // Update the appropriate enabled field based on scope
+                    match change.scope {
+                        Scope::User => p.enabled_user = Some(change.enabled),
+                        Scope::Project => p.enabled_project = Some(change.enabled),
+                        Scope::Local => p.enabled_local = Some(change.enabled),
                     }
-                    self.message = Some(StatusMessage::info(format!("Disabled {}", id)));
                 }
-                Err(e) => {
-                    self.message = Some(StatusMessage::error(format!("Failed to disable: {}", e)));
+                self.collisions = find_collisions(&self.plugins);
+                if let Ok(discovery) = PluginDiscovery::new() {
+                    self.reload_mcp_servers(&discovery);
                 }
+                self.expiring = self.service.load_expiring().expiring;
+                self.message = Some(StatusMessage::info(format!(
+                    "{} {} in {} scope",
+                    change.plugin_id,
+                    if change.enabled {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    },
+                    change.scope
+                )));
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage::error(format!("Failed to apply: {}", e)));
             }
         }
     }
 
+    /// Run the stdio handshake check on the selected plugin's MCP servers.
+    /// Blocks for at most the timeout per server; results show in the detail modal.
+    pub fn check_selected_mcp_servers(&mut self) {
+        let Some(plugin) = self.selected_plugin() else {
+            return;
+        };
+        let Some(root) = plugin.install_path.clone() else {
+            self.message = Some(StatusMessage::error("Plugin is not installed"));
+            return;
+        };
+        let servers: Vec<_> = plugin
+            .contents
+            .mcp_servers
+            .iter()
+            .filter(|s| s.transport() == "stdio")
+            .cloned()
+            .collect();
+        if servers.is_empty() {
+            self.message = Some(StatusMessage::info("No stdio MCP servers to check"));
+            return;
+        }
+
+        let plugin_id = plugin.id.clone();
+        let reports: Vec<McpCheckReport> = servers
+            .iter()
+            .map(|s| check_stdio_server(s, &root, MCP_CHECK_TIMEOUT))
+            .collect();
+        let failed = reports.iter().filter(|r| !r.is_ok()).count();
+        self.message = Some(if failed == 0 {
+            StatusMessage::info(format!("MCP check: {} server(s) ok", reports.len()))
+        } else {
+            StatusMessage::error(format!(
+                "MCP check: {} of {} server(s) failed",
+                failed,
+                reports.len()
+            ))
+        });
+        self.mcp_checks = Some((plugin_id, reports));
+    }
+
+    /// Results of the last MCP check, if it was run for this plugin
+    pub fn mcp_checks_for(&self, plugin_id: &str) -> &[McpCheckReport] {
+        match self.mcp_checks {
+            Some((ref id, ref reports)) if id == plugin_id => reports,
+            _ => &[],
+        }
+    }
+
     pub fn show_help(&mut self) {
         self.mode = AppMode::Help;
     }
@@ -262,10 +829,34 @@ impl App {
 
     pub fn show_detail_modal(&mut self) {
         if self.selected_plugin().is_some() {
+            self.load_changelog();
             self.mode = AppMode::DetailModal;
         }
     }
 
+    /// Load the changelog of the selected plugin when an update is available
+    fn load_changelog(&mut self) {
+        self.changelog = None;
+        let Some(id) = self.selected_plugin().map(|p| p.id.clone()) else {
+            return;
+        };
+        if !self.update_for(&id).is_some_and(|u| u.status.has_update()) {
+            return;
+        }
+        let changelog = PluginDiscovery::new()
+            .map_err(|e| e.to_string())
+            .and_then(|discovery| discovery.changelog(&id).map_err(|e| e.to_string()));
+        self.changelog = Some((id, changelog));
+    }
+
+    /// Changelog loaded for this plugin, if any
+    pub fn changelog_for(&self, plugin_id: &str) -> Option<&Result<Changelog, String>> {
+        match self.changelog {
+            Some((ref id, ref changelog)) if id == plugin_id => Some(changelog),
+            _ => None,
+        }
+    }
+
     pub fn hide_detail_modal(&mut self) {
         self.mode = AppMode::Normal;
     }
@@ -285,13 +876,29 @@ impl App {
     }
 
     pub fn cancel_confirm(&mut self) {
+        self.pending_change = None;
+        self.enable_for = None;
         self.mode = AppMode::Normal;
     }
 
     pub fn execute_confirm(&mut self) {
-        if let AppMode::Confirm(ConfirmAction::Remove) = self.mode {
-            // Remove functionality placeholder
-            self.message = Some(StatusMessage::info("Remove not yet implemented"));
+        match self.mode {
+            AppMode::Confirm(ConfirmAction::Remove) => {
+                // Remove functionality placeholder
+                self.message = Some(StatusMessage::info("Remove not yet implemented"));
+            }
+            AppMode::Confirm(ConfirmAction::ApplyPlan) => {
+                if let Some(change) = self.pending_change.take() {
+                    self.apply_change(change);
+                }
+            }
+            AppMode::Confirm(ConfirmAction::EnableWithHooks) => {
+                // May open the plan confirmation for a plugin in another project
+                self.mode = AppMode::Normal;
+                self.change_selected_plugin(true);
+                return;
+            }
+            _ => {}
         }
         self.mode = AppMode::Normal;
     }
@@ -300,6 +907,18 @@ impl App {
         self.should_quit = true;
     }
 
+    /// Collisions the given plugin takes part in
+    pub fn collisions_for<'a>(&'a self, plugin_id: &'a str) -> impl Iterator<Item = &'a Collision> {
+        self.collisions
+            .iter()
+            .filter(move |c| c.involves(plugin_id))
+    }
+
+    /// Estimated context footprint of the plugins active in this project
+    pub fn context_cost(&self) -> ContextCost {
+        ContextCost::of_active(&self.plugins)
+    }
+
     pub fn plugin_count(&self) -> (usize, usize) {
         let enabled = self.plugins.iter().filter(|p| p.is_enabled()).count();
         (enabled, self.plugins.len())
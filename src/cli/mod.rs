@@ -1,6 +1,15 @@
-use crate::plugin::{PluginDiscovery, PluginService, Scope, ScopeFilter};
+use crate::plugin::{
+    check_stdio_server, find_collisions, find_providers, format_duration, format_timestamp,
+    format_tokens, parse_duration, parse_marketplace_source, plan_build_marketplace,
+    plan_new_marketplace, plan_new_plugin, run_with_plugin_dirs, short_commit, validate_plugin,
+    BisectVerdict, ChangePlan, ContextCost, NewPlugin, Plugin, PluginContents, PluginDiscovery,
+    PluginService, PluginUpdate, Scope, ScopeFilter, Severity, UpdateStatus, MCP_SERVER_BYTES,
+};
+use chrono::{Local, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::Result;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "ccpm")]
@@ -18,7 +27,7 @@ pub enum Commands {
     List {
         /// Filter by scope
         #[arg(short, long, value_enum, default_value = "all")]
-        scope: ScopeArg,
+        scope: ScopeFilterArg,
 
         /// Show only enabled plugins
         #[arg(short, long)]
@@ -41,6 +50,14 @@ pub enum Commands {
         /// Scope to enable in
         #[arg(short, long, value_enum, default_value = "user")]
         scope: ScopeArg,
+
+        /// Revert to the previous setting after this long (e.g. 45m, 2h, 1d)
+        #[arg(long = "for", value_name = "DURATION", value_parser = parse_duration_arg)]
+        for_duration: Option<chrono::Duration>,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
     },
 
     /// Disable a plugin
@@ -51,30 +68,467 @@ pub enum Commands {
         /// Scope to disable in
         #[arg(short, long, value_enum, default_value = "user")]
         scope: ScopeArg,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
     },
 
     /// Show plugin details
     Info {
         /// Plugin ID (name@marketplace)
         plugin: String,
+
+        /// Also list the commands, agents, skills, hooks and MCP servers it provides
+        #[arg(long)]
+        contents: bool,
+    },
+
+    /// Find which plugin provides a command, agent or skill
+    Which {
+        /// Name as used in a session, e.g. /review, my-plugin:review or reviewer
+        name: String,
+    },
+
+    /// Check the plugins enabled in this project for problems (exits 1 if any are found)
+    Doctor,
+
+    /// List the hooks of installed plugins and flag risky commands
+    Hooks {
+        /// Only show this plugin (name@marketplace)
+        plugin: Option<String>,
+
+        /// Only show hooks with risk flags
+        #[arg(long)]
+        risky: bool,
+    },
+
+    /// Inspect MCP servers
+    Mcp {
+        #[command(subcommand)]
+        command: McpCommands,
+    },
+
+    /// Lint a plugin directory: manifest, referenced files, frontmatter and hooks
+    /// (exits 1 if any errors are found)
+    Validate {
+        /// Plugin root, the directory containing .claude-plugin/plugin.json
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+    },
+
+    /// Scaffold a new plugin or marketplace
+    New {
+        #[command(subcommand)]
+        command: NewCommands,
+    },
+
+    /// Maintain a local marketplace
+    Marketplace {
+        #[command(subcommand)]
+        command: MarketplaceCommands,
+    },
+
+    /// Register local plugin directories for development (loaded with claude --plugin-dir)
+    Dev {
+        #[command(subcommand)]
+        command: DevCommands,
+    },
+
+    /// Launch Claude with dev plugins and temporary enable/disable overrides;
+    /// the local settings are restored when it exits
+    Run {
+        /// Enable a plugin for this session (repeatable)
+        #[arg(long = "with", value_name = "PLUGIN")]
+        with: Vec<String>,
+
+        /// Disable a plugin for this session (repeatable)
+        #[arg(long = "without", value_name = "PLUGIN")]
+        without: Vec<String>,
+
+        /// Also load this plugin directory, besides the registered dev plugins (repeatable)
+        #[arg(long = "dev", value_name = "PATH")]
+        dev: Vec<PathBuf>,
+
+        /// Command to run after `--`, usually `claude` and its arguments
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
+    /// Find the plugin causing a problem by enabling half of the suspects at a time
+    Bisect {
+        #[command(subcommand)]
+        command: BisectCommands,
+    },
+
+    /// Revert expired `enable --for` overrides and restore settings left by
+    /// interrupted `ccpm run` sessions (also done at every ccpm start)
+    Gc {
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// List installed plugins with updates in their local marketplace checkout
+    /// (nothing is fetched; run `claude plugin marketplace update` first)
+    Outdated {
+        /// Include plugins that are up to date or could not be checked
+        #[arg(short, long)]
+        all: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Fetch marketplace checkouts and update plugins from them. The previous
+    /// cached copy is kept for rollback.
+    Update {
+        /// Plugin IDs (name@marketplace)
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        plugins: Vec<String>,
+
+        /// Update every plugin with an update available
+        #[arg(long)]
+        all: bool,
+
+        /// Use the marketplace checkouts as they are, without git fetch
+        #[arg(long)]
+        no_fetch: bool,
+
+        /// Print the cache copies and file changes instead of updating; nothing is
+        /// fetched (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Show the commits and component changes an update would bring, from the
+    /// installed commit to the marketplace checkout's HEAD
+    Changelog {
+        /// Plugin ID (name@marketplace)
+        plugin: String,
+    },
+
+    /// Hold a plugin at a version so updates skip it (defaults to the installed
+    /// version and commit)
+    Pin {
+        /// Plugin ID (name@marketplace)
+        plugin: String,
+
+        /// Only allow updates to this version
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only allow updates to this commit
+        #[arg(long)]
+        commit: Option<String>,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Remove a plugin's pin
+    Unpin {
+        /// Plugin ID (name@marketplace)
+        plugin: String,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Switch a plugin back to the cached version its last update replaced
+    /// (run again to undo)
+    Rollback {
+        /// Plugin ID (name@marketplace)
+        plugin: String,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Rank the plugins enabled in this project by estimated context cost
+    Budget {
+        /// Include every discovered plugin, not only those enabled here
+        #[arg(short, long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum McpCommands {
+    /// Start a plugin's stdio MCP servers and run the initialize/tools/list handshake
+    /// (exits 1 if any server fails)
+    Check {
+        /// Plugin ID (name@marketplace)
+        plugin: String,
+
+        /// Only check this server
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Seconds to wait for each server
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NewCommands {
+    /// Create a plugin skeleton: manifest, example command, agent and hook, and a README
+    Plugin {
+        /// Plugin name (kebab-case); also the directory name
+        name: String,
+
+        /// Directory to create the plugin in
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+
+        /// One-line description for the manifest
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Author name for the manifest
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Print the files that would be created instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Create .claude-plugin/marketplace.json listing the plugins in a directory
+    Marketplace {
+        /// Marketplace root
+        dir: PathBuf,
+
+        /// Marketplace name (defaults to the directory name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Owner name for the catalog
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Print what would be created instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MarketplaceCommands {
+    /// List registered marketplaces
+    List,
+
+    /// Register a marketplace from a local directory, a git URL or a GitHub owner/repo,
+    /// or one this project declares in extraKnownMarketplaces
+    Add {
+        /// Directory, git URL, owner/repo, or the name of a pending marketplace
+        #[arg(required_unless_present = "pending")]
+        source: Option<String>,
+
+        /// Register under this name instead of the one in its marketplace.json
+        #[arg(long, conflicts_with = "pending")]
+        name: Option<String>,
+
+        /// Register every marketplace this project declares that is not added yet
+        #[arg(long, conflicts_with = "source")]
+        pending: bool,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Unregister a marketplace and delete its checkout
+    Remove {
+        /// Marketplace name
+        name: String,
+
+        /// Remove it even when installed plugins come from it
+        #[arg(long, short)]
+        yes: bool,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Show a marketplace's source, checkout and catalog
+    Info {
+        /// Marketplace name
+        name: String,
+    },
+
+    /// Turn auto-update on or off for a marketplace
+    AutoUpdate {
+        /// Marketplace name
+        name: String,
+
+        #[arg(value_enum)]
+        state: Switch,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Refresh marketplace.json from the plugins in the directory
+    Build {
+        /// Marketplace root
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevCommands {
+    /// Register a plugin directory; it is validated on every reload
+    Add {
+        /// Plugin root, the directory containing .claude-plugin/plugin.json
+        path: PathBuf,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// Unregister a plugin directory (the files are left alone)
+    Remove {
+        /// Plugin directory or name
+        plugin: String,
+
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+
+    /// List registered plugins with their validation results
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum BisectCommands {
+    /// Save the local settings and the plugins active here, then test the first half
+    Start {
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+    /// The problem did not occur with the plugins in this step
+    Good {
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
     },
+    /// The problem occurred with the plugins in this step
+    Bad {
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+    /// Stop bisecting and restore the original local settings
+    Reset {
+        /// Print what would change instead of writing (diff or json-patch)
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true, default_missing_value = "diff")]
+        dry_run: Option<DryRunFormat>,
+    },
+}
+
+impl BisectCommands {
+    fn dry_run(&self) -> Option<DryRunFormat> {
+        match self {
+            BisectCommands::Start { dry_run }
+            | BisectCommands::Good { dry_run }
+            | BisectCommands::Bad { dry_run }
+            | BisectCommands::Reset { dry_run } => *dry_run,
+        }
+    }
+}
+
+impl Commands {
+    /// Whether the command only previews its changes with `--dry-run`
+    pub fn is_dry_run(&self) -> bool {
+        let dry_run = match self {
+            Commands::Enable { dry_run, .. }
+            | Commands::Disable { dry_run, .. }
+            | Commands::Update { dry_run, .. }
+            | Commands::Pin { dry_run, .. }
+            | Commands::Unpin { dry_run, .. }
+            | Commands::Rollback { dry_run, .. }
+            | Commands::Gc { dry_run } => dry_run,
+            Commands::Bisect { command } => return command.dry_run().is_some(),
+            Commands::New {
+                command:
+                    NewCommands::Plugin { dry_run, .. } | NewCommands::Marketplace { dry_run, .. },
+            } => dry_run,
+            Commands::Marketplace {
+                command:
+                    MarketplaceCommands::Add { dry_run, .. }
+                    | MarketplaceCommands::Remove { dry_run, .. }
+                    | MarketplaceCommands::AutoUpdate { dry_run, .. }
+                    | MarketplaceCommands::Build { dry_run, .. },
+            } => dry_run,
+            Commands::Dev {
+                command: DevCommands::Add { dry_run, .. } | DevCommands::Remove { dry_run, .. },
+            } => dry_run,
+            _ => return false,
+        };
+        dry_run.is_some()
+    }
 }
 
+/// Scope `list` filters by
 #[derive(Clone, Copy, ValueEnum)]
-pub enum ScopeArg {
+pub enum ScopeFilterArg {
     All,
     User,
     Project,
     Local,
+    Dev,
 }
 
-impl From<ScopeArg> for ScopeFilter {
-    fn from(arg: ScopeArg) -> Self {
+/// Settings scope a command writes to
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ScopeArg {
+    User,
+    Project,
+    Local,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Switch {
+    On,
+    Off,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DryRunFormat {
+    Diff,
+    JsonPatch,
+}
+
+impl From<ScopeFilterArg> for ScopeFilter {
+    fn from(arg: ScopeFilterArg) -> Self {
         match arg {
-            ScopeArg::All => ScopeFilter::All,
-            ScopeArg::User => ScopeFilter::User,
-            ScopeArg::Project => ScopeFilter::Project,
-            ScopeArg::Local => ScopeFilter::Local,
+            ScopeFilterArg::All => ScopeFilter::All,
+            ScopeFilterArg::User => ScopeFilter::User,
+            ScopeFilterArg::Project => ScopeFilter::Project,
+            ScopeFilterArg::Local => ScopeFilter::Local,
+            ScopeFilterArg::Dev => ScopeFilter::Dev,
         }
     }
 }
@@ -82,7 +536,7 @@ impl From<ScopeArg> for ScopeFilter {
 impl From<ScopeArg> for Scope {
     fn from(arg: ScopeArg) -> Self {
         match arg {
-            ScopeArg::All | ScopeArg::User => Scope::User,
+            ScopeArg::User => Scope::User,
             ScopeArg::Project => Scope::Project,
             ScopeArg::Local => Scope::Local,
         }
@@ -97,10 +551,172 @@ pub fn run_command(cmd: Commands) -> Result<()> {
             disabled,
             debug,
         } => list_plugins(scope.into(), enabled, disabled, debug),
-        Commands::Enable { plugin, scope } => enable_plugin(&plugin, scope.into()),
-        Commands::Disable { plugin, scope } => disable_plugin(&plugin, scope.into()),
-        Commands::Info { plugin } => show_info(&plugin),
+        Commands::Enable {
+            plugin,
+            scope,
+            for_duration: Some(duration),
+            dry_run,
+        } => enable_for(&plugin, scope.into(), duration, dry_run),
+        Commands::Enable {
+            plugin,
+            scope,
+            for_duration: None,
+            dry_run,
+        } => set_plugin_enabled(&plugin, scope.into(), true, dry_run),
+        Commands::Disable {
+            plugin,
+            scope,
+            dry_run,
+        } => set_plugin_enabled(&plugin, scope.into(), false, dry_run),
+        Commands::Info { plugin, contents } => show_info(&plugin, contents),
+        Commands::Which { name } => which(&name),
+        Commands::Doctor => doctor(),
+        Commands::Budget { all } => budget(all),
+        Commands::Outdated { all, format } => outdated(all, format),
+        Commands::Changelog { plugin } => changelog(&plugin),
+        Commands::Pin {
+            plugin,
+            version,
+            commit,
+            dry_run,
+        } => apply_plan(
+            PluginService::new()?.plan_pin(&plugin, version, commit)?,
+            dry_run,
+        ),
+        Commands::Unpin { plugin, dry_run } => {
+            apply_plan(PluginService::new()?.plan_unpin(&plugin)?, dry_run)
+        }
+        Commands::Rollback { plugin, dry_run } => rollback(&plugin, dry_run),
+        Commands::Update {
+            plugins,
+            all,
+            no_fetch,
+            dry_run,
+        } => update(&plugins, all, no_fetch, dry_run),
+        Commands::Mcp {
+            command:
+                McpCommands::Check {
+                    plugin,
+                    server,
+                    timeout,
+                },
+        } => mcp_check(&plugin, server.as_deref(), timeout),
+        Commands::Hooks { plugin, risky } => list_hooks(plugin.as_deref(), risky),
+        Commands::Validate { dir } => validate(&dir),
+        Commands::New {
+            command:
+                NewCommands::Plugin {
+                    name,
+                    dir,
+                    description,
+                    author,
+                    dry_run,
+                },
+        } => {
+            let options = NewPlugin {
+                name,
+                description,
+                author,
+            };
+            apply_plan(plan_new_plugin(&dir, &options)?, dry_run)
+        }
+        Commands::New {
+            command:
+                NewCommands::Marketplace {
+                    dir,
+                    name,
+                    owner,
+                    dry_run,
+                },
+        } => apply_plan(
+            plan_new_marketplace(&dir, name.as_deref(), owner.as_deref())?,
+            dry_run,
+        ),
+        Commands::Marketplace { command } => marketplace(command),
+        Commands::Dev {
+            command: DevCommands::Add { path, dry_run },
+        } => apply_plan(PluginService::new()?.plan_add_dev_plugin(&path)?, dry_run),
+        Commands::Dev {
+            command: DevCommands::Remove { plugin, dry_run },
+        } => apply_plan(
+            PluginService::new()?.plan_remove_dev_plugin(&plugin)?,
+            dry_run,
+        ),
+        Commands::Dev {
+            command: DevCommands::List,
+        } => list_dev_plugins(),
+        Commands::Run {
+            with,
+            without,
+            dev,
+            command,
+        } => run_claude(&with, &without, dev, &command),
+        Commands::Bisect { command } => bisect(command),
+        Commands::Gc {
+            dry_run: Some(format),
+        } => {
+            let service = PluginService::new()?;
+            if let DryRunFormat::Diff = format {
+                for path in service.interrupted_runs() {
+                    println!(
+                        "Would restore {} after an interrupted ccpm run",
+                        path.display()
+                    );
+                }
+            }
+            let (plan, _) = service.plan_revert_expired(Utc::now())?;
+            print_plan(&plan, format)
+        }
+        Commands::Gc { dry_run: None } => {
+            if housekeeping() == 0 {
+                println!("Nothing to clean up.");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Put back settings left overridden by a `ccpm run` whose process was killed, and
+/// revert `enable --for` overrides that have expired. Returns how many were handled.
+pub fn housekeeping() -> usize {
+    let Ok(service) = PluginService::new() else {
+        return 0;
+    };
+    let mut handled = 0;
+    match service.recover_runs() {
+        Ok(restored) => {
+            for path in restored {
+                eprintln!("Restored {} after an interrupted ccpm run", path.display());
+                handled += 1;
+            }
+        }
+        Err(e) => eprintln!(
+            "Failed to restore settings from an interrupted ccpm run: {}",
+            e
+        ),
     }
+    match service.revert_expired() {
+        Ok(expired) => {
+            for entry in expired {
+                eprintln!(
+                    "Reverted {} in {} scope (enabled until {})",
+                    entry.plugin_id,
+                    entry.scope,
+                    entry
+                        .expires_at
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                );
+                handled += 1;
+            }
+        }
+        Err(e) => eprintln!("Failed to revert expired enables: {}", e),
+    }
+    handled
+}
+
+fn parse_duration_arg(text: &str) -> std::result::Result<chrono::Duration, String> {
+    parse_duration(text).ok_or_else(|| format!("invalid duration '{}' (try 45m, 2h or 1d)", text))
 }
 
 fn list_plugins(scope_filter: ScopeFilter, only_enabled: bool, only_disabled: bool, debug: bool) -> Result<()> {
@@ -126,12 +742,7 @@ fn list_plugins(scope_filter: ScopeFilter, only_enabled: bool, only_disabled: bo
 
     let filtered: Vec<_> = plugins
         .iter()
-        .filter(|p| match scope_filter {
-            ScopeFilter::All => true,
-            ScopeFilter::User => p.install_scope == Scope::User,
-            ScopeFilter::Project => p.install_scope == Scope::Project,
-            ScopeFilter::Local => p.install_scope == Scope::Local,
-        })
+        .filter(|p| scope_filter.matches(p))
         .filter(|p| {
             if only_enabled {
                 p.is_enabled()
@@ -180,21 +791,96 @@ fn list_plugins(scope_filter: ScopeFilter, only_enabled: bool, only_disabled: bo
     Ok(())
 }
 
-fn enable_plugin(plugin_id: &str, scope: Scope) -> Result<()> {
+fn set_plugin_enabled(
+    plugin_id: &str,
+    scope: Scope,
+    enabled: bool,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
     let service = PluginService::new()?;
-    service.enable_plugin(plugin_id, scope)?;
-    println!("Enabled {} in {} scope", plugin_id, scope);
+    let plan = service.plan_set_enabled(plugin_id, scope, None, enabled)?;
+
+    if let Some(format) = dry_run {
+        print_plan(&plan, format)?;
+        return Ok(());
+    }
+
+    service.apply(&plan)?;
+    println!(
+        "{} {} in {} scope",
+        if enabled { "Enabled" } else { "Disabled" },
+        plugin_id,
+        scope
+    );
     Ok(())
 }
 
-fn disable_plugin(plugin_id: &str, scope: Scope) -> Result<()> {
+fn enable_for(
+    plugin_id: &str,
+    scope: Scope,
+    duration: chrono::Duration,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
     let service = PluginService::new()?;
-    service.disable_plugin(plugin_id, scope)?;
-    println!("Disabled {} in {} scope", plugin_id, scope);
+    let expires_at = Utc::now() + duration;
+    let plan = service.plan_enable_until(plugin_id, scope, None, expires_at)?;
+
+    if let Some(format) = dry_run {
+        print_plan(&plan, format)?;
+        return Ok(());
+    }
+
+    service.apply(&plan)?;
+    println!(
+        "Enabled {} in {} scope for {} (until {})",
+        plugin_id,
+        scope,
+        format_duration(duration),
+        expires_at.with_timezone(&Local).format("%H:%M")
+    );
+    Ok(())
+}
+
+/// Write a plan (or print it for `--dry-run`) and list the files it touched
+fn apply_plan(plan: ChangePlan, dry_run: Option<DryRunFormat>) -> Result<()> {
+    if let Some(format) = dry_run {
+        return print_plan(&plan, format);
+    }
+    if plan.is_empty() {
+        println!("Already up to date.");
+        return Ok(());
+    }
+
+    PluginService::new()?.apply(&plan)?;
+    for change in &plan.changes {
+        let verb = if change.before.is_some() {
+            "Updated"
+        } else {
+            "Created"
+        };
+        println!("{} {}", verb, change.path.display());
+    }
+    Ok(())
+}
+
+/// Print a change plan for `--dry-run` without touching any file
+fn print_plan(plan: &ChangePlan, format: DryRunFormat) -> Result<()> {
+    match format {
+        DryRunFormat::Diff => {
+            if plan.is_empty() {
+                println!("No changes.");
+            } else {
+                print!("{}", plan.unified_diff());
+            }
+        }
+        DryRunFormat::JsonPatch => {
+            println!("{}", serde_json::to_string_pretty(&plan.json_patch())?);
+        }
+    }
     Ok(())
 }
 
-fn show_info(plugin_id: &str) -> Result<()> {
+fn show_info(plugin_id: &str, show_contents: bool) -> Result<()> {
     let discovery = PluginDiscovery::new()?;
     let plugins = discovery.discover_all()?;
 
@@ -204,6 +890,9 @@ fn show_info(plugin_id: &str) -> Result<()> {
         Some(p) => {
             println!("Name:        {}", p.name);
             println!("Marketplace: {}", p.marketplace);
+            if let Some(ref source) = p.marketplace_source {
+                println!("Source:      {}", source);
+            }
             println!("ID:          {}", p.id);
             println!(
                 "Status:      {}",
@@ -244,6 +933,22 @@ fn show_info(plugin_id: &str) -> Result<()> {
                 println!("Author:      {}", author_str);
             }
 
+            if let Some(ref homepage) = p.homepage {
+                println!("Homepage:    {}", homepage);
+            }
+
+            if let Some(ref repository) = p.repository {
+                println!("Repository:  {}", repository);
+            }
+
+            if let Some(ref license) = p.license {
+                println!("License:     {}", license);
+            }
+
+            if !p.keywords.is_empty() {
+                println!("Keywords:    {}", p.keywords.join(", "));
+            }
+
             if let Some(ref path) = p.install_path {
                 println!("Path:        {}", path.display());
             }
@@ -251,6 +956,12 @@ fn show_info(plugin_id: &str) -> Result<()> {
             if let Some(ref desc) = p.description {
                 println!("\nDescription:\n{}", desc);
             }
+
+            if show_contents {
+                print_contents(&p.contents);
+            } else {
+                println!("\nContents:    {}", p.contents.summary());
+            }
         }
         None => {
             println!("Plugin '{}' not found.", plugin_id);
@@ -259,3 +970,1041 @@ fn show_info(plugin_id: &str) -> Result<()> {
 
     Ok(())
 }
+
+fn print_contents(contents: &PluginContents) {
+    println!("\nContents:    {}", contents.summary());
+
+    let sections = [
+        ("Commands", "/", &contents.commands),
+        ("Agents", "", &contents.agents),
+        ("Skills", "", &contents.skills),
+    ];
+    for (title, prefix, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        println!("\n{}:", title);
+        for item in items {
+            let name = format!("{}{}", prefix, item.name);
+            println!(
+                "  {:<28} {}",
+                name,
+                item.description.as_deref().unwrap_or("")
+            );
+            println!("  {:<28} {}", "", item.path.display());
+        }
+    }
+
+    if !contents.hooks.is_empty() {
+        println!("\nHooks:");
+        for hook in &contents.hooks {
+            let event = match hook.matcher {
+                Some(ref matcher) => format!("{} [{}]", hook.event, matcher),
+                None => hook.event.clone(),
+            };
+            println!(
+                "  {:<28} {}",
+                event,
+                hook.command.as_deref().unwrap_or(&hook.hook_type)
+            );
+        }
+    }
+
+    if !contents.mcp_servers.is_empty() {
+        println!("\nMCP servers:");
+        for server in &contents.mcp_servers {
+            let name = format!("{} ({})", server.name, server.transport());
+            println!("  {:<28} {}", name, server.target());
+        }
+    }
+}
+
+fn which(name: &str) -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+    let providers = find_providers(&plugins, name);
+
+    if providers.is_empty() {
+        println!("No plugin provides '{}'.", name);
+        return Ok(());
+    }
+
+    for (i, provider) in providers.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} ({}) from {}",
+            provider.kind.invocation(&provider.item.name),
+            provider.kind,
+            provider.plugin.id
+        );
+        println!("  Path:    {}", provider.item.path.display());
+        println!("  Status:  {}", activity(provider.plugin));
+    }
+
+    Ok(())
+}
+
+/// Whether a plugin is loaded in the current project, and which scope decides it
+fn activity(plugin: &Plugin) -> String {
+    if !plugin.is_current_project {
+        return match plugin.project_path_display() {
+            Some(project) => format!("not loaded here (installed in {})", project),
+            None => "not loaded here (installed in another project)".to_string(),
+        };
+    }
+    match (plugin.is_enabled(), plugin.effective_scope()) {
+        (true, Some(scope)) => format!("enabled in this project (by {} settings)", scope),
+        (false, Some(scope)) => format!("disabled (by {} settings)", scope),
+        (_, None) => "disabled (not enabled in any scope)".to_string(),
+    }
+}
+
+fn doctor() -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+    let active = plugins.iter().filter(|p| p.is_active()).count();
+    println!("Checked {} plugins enabled in this project.", active);
+
+    let collisions = find_collisions(&plugins);
+    if !collisions.is_empty() {
+        println!("\nCollisions:");
+        for collision in &collisions {
+            println!(
+                "  {} ({}) is defined by {}",
+                collision.kind.invocation(&collision.name),
+                collision.kind,
+                collision.plugin_ids.join(", ")
+            );
+        }
+    }
+
+    let problems = collisions.len();
+    if problems == 0 {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    println!(
+        "\n{} problem{} found.",
+        problems,
+        if problems == 1 { "" } else { "s" }
+    );
+    std::process::exit(1);
+}
+
+fn validate(dir: &Path) -> Result<()> {
+    let mut diagnostics = validate_plugin(dir);
+    for diagnostic in &mut diagnostics {
+        // "./commands/x.md" reads better as "commands/x.md"
+        if let Ok(relative) = diagnostic.path.strip_prefix(".") {
+            diagnostic.path = relative.to_path_buf();
+        }
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if diagnostics.is_empty() {
+        println!("{}: no problems found.", dir.display());
+        return Ok(());
+    }
+
+    println!(
+        "\n{} error{}, {} warning{}.",
+        errors,
+        if errors == 1 { "" } else { "s" },
+        warnings,
+        if warnings == 1 { "" } else { "s" }
+    );
+    if errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_claude(
+    with: &[String],
+    without: &[String],
+    dev: Vec<PathBuf>,
+    command: &[String],
+) -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+    for id in with.iter().chain(without) {
+        if !plugins.iter().any(|p| !p.is_dev && p.id == *id) {
+            println!("Plugin '{}' not found.", id);
+            std::process::exit(1);
+        }
+    }
+
+    let mut plugin_dirs: Vec<PathBuf> = plugins
+        .iter()
+        .filter(|p| p.is_dev)
+        .filter_map(|p| p.install_path.clone())
+        .collect();
+    for dir in dev {
+        if !plugin_dirs.contains(&dir) {
+            plugin_dirs.push(dir);
+        }
+    }
+
+    let service = PluginService::new()?;
+    let plan = service.plan_run_overrides(with, without)?;
+    let session = service.start_run(&plan)?;
+    let status = run_with_plugin_dirs(command, &plugin_dirs);
+    session.restore()?;
+
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => {
+            println!("Failed to start {}: {}", command[0], e);
+            std::process::exit(1);
+        }
+    };
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+fn bisect(command: BisectCommands) -> Result<()> {
+    let service = PluginService::new()?;
+    let planned = match command {
+        BisectCommands::Start { .. } => {
+            let plugins = PluginDiscovery::new()?.discover_all()?;
+            let ids: Vec<String> = plugins
+                .iter()
+                .filter(|p| p.is_active() && !p.is_dev)
+                .map(|p| p.id.clone())
+                .collect();
+            if ids.len() < 2 {
+                println!(
+                    "Bisect needs at least two plugins enabled in this project ({} found).",
+                    ids.len()
+                );
+                std::process::exit(1);
+            }
+            service.plan_start_bisect(ids)?
+        }
+        BisectCommands::Good { .. } => service.plan_mark_bisect(BisectVerdict::Good)?,
+        BisectCommands::Bad { .. } => service.plan_mark_bisect(BisectVerdict::Bad)?,
+        BisectCommands::Reset { .. } => service.plan_reset_bisect()?,
+    };
+
+    if let Some(format) = command.dry_run() {
+        if let DryRunFormat::Diff = format {
+            for path in planned.removals() {
+                println!("Would remove {}", path.display());
+            }
+        }
+        return apply_plan(planned.plan, Some(format));
+    }
+    service.apply_bisect(&planned)?;
+    let state = planned.state;
+    if let BisectCommands::Reset { .. } = command {
+        println!("Bisect reset. Restored {}", state.settings_path.display());
+        return Ok(());
+    }
+
+    if state.is_done() {
+        let steps = format!(
+            "{} step{}",
+            state.steps,
+            if state.steps == 1 { "" } else { "s" }
+        );
+        match state.culprit() {
+            Some(culprit) => {
+                println!("Culprit: {}", culprit);
+                println!("Found after {}.", steps);
+            }
+            None => println!(
+                "No culprit found after {}: the problem did not occur with any of the {} plugins.",
+                steps,
+                state.plugins.len()
+            ),
+        }
+        println!("Restored {}", state.settings_path.display());
+        return Ok(());
+    }
+
+    let testing = state.testing();
+    println!(
+        "Step {}: testing {} of {} suspects (about {} step{} left)",
+        state.steps + 1,
+        testing.len(),
+        state.suspects.len(),
+        state.steps_left(),
+        if state.steps_left() == 1 { "" } else { "s" }
+    );
+    println!("Enabled in local scope:");
+    for id in testing {
+        println!("  {}", id);
+    }
+    println!(
+        "\nRestart Claude Code and try to reproduce the problem, then run `ccpm bisect good` or `ccpm bisect bad`."
+    );
+    Ok(())
+}
+
+fn marketplace(command: MarketplaceCommands) -> Result<()> {
+    match command {
+        MarketplaceCommands::Build { dir, dry_run } => {
+            apply_plan(plan_build_marketplace(&dir)?, dry_run)
+        }
+        MarketplaceCommands::List => list_marketplaces(),
+        MarketplaceCommands::Add {
+            source,
+            name,
+            pending,
+            dry_run,
+        } => add_marketplace(source.as_deref(), name.as_deref(), pending, dry_run),
+        MarketplaceCommands::Remove { name, yes, dry_run } => {
+            remove_marketplace(&name, yes, dry_run)
+        }
+        MarketplaceCommands::Info { name } => marketplace_info(&name),
+        MarketplaceCommands::AutoUpdate {
+            name,
+            state,
+            dry_run,
+        } => apply_plan(
+            PluginService::new()?.plan_set_auto_update(&name, matches!(state, Switch::On))?,
+            dry_run,
+        ),
+    }
+}
+
+fn add_marketplace(
+    source: Option<&str>,
+    name: Option<&str>,
+    all_pending: bool,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let service = PluginService::new()?;
+    // Messages would break a json-patch on stdout
+    let quiet = matches!(dry_run, Some(DryRunFormat::JsonPatch));
+    let pending = PluginDiscovery::new()?.pending_marketplaces();
+
+    // A declared name is only taken as such when no directory by that name exists
+    let selected: Vec<_> = match source {
+        _ if all_pending => pending.iter().collect(),
+        Some(source) if !Path::new(source).is_dir() => {
+            pending.iter().filter(|p| p.name == source).collect()
+        }
+        _ => Vec::new(),
+    };
+    if all_pending && selected.is_empty() {
+        println!("No pending marketplaces in this project.");
+        return Ok(());
+    }
+
+    // Plans are applied (or previewed) one at a time: each owns the same staging clone
+    let mut preview = ChangePlan::new();
+    let mut register = |source, name: Option<&str>| -> Result<String> {
+        let planned = service.plan_register_marketplace(source, name)?;
+        match dry_run {
+            Some(_) => {
+                if !quiet && planned.staging().is_some() {
+                    println!(
+                        "Would clone {} to {}",
+                        planned.entry.source.location(),
+                        planned.entry.install_location.display()
+                    );
+                }
+                preview.extend(planned.plan.clone());
+            }
+            None => service.apply_marketplace(&planned)?,
+        }
+        Ok(planned.name.clone())
+    };
+
+    let mut added = Vec::new();
+    let mut failed = 0;
+    if selected.is_empty() {
+        added.push(register(
+            parse_marketplace_source(source.unwrap_or_default()),
+            name,
+        )?);
+    } else {
+        for marketplace in selected {
+            match register(
+                marketplace.source.clone(),
+                Some(name.unwrap_or(&marketplace.name)),
+            ) {
+                Ok(name) => added.push(name),
+                Err(e) => {
+                    println!("Failed to add {}: {}", marketplace.name, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+    if let Some(format) = dry_run {
+        if !quiet {
+            for name in &added {
+                println!("Would add marketplace {}", name);
+            }
+        }
+        print_plan(&preview, format)?;
+    } else {
+        for name in &added {
+            println!("Added marketplace {}", name);
+        }
+        if !added.is_empty() {
+            println!("Install plugins from Claude Code with /plugin");
+        }
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn remove_marketplace(name: &str, yes: bool, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let service = PluginService::new()?;
+    let planned = service.plan_remove_marketplace(name)?;
+    // Messages would break a json-patch on stdout
+    let quiet = matches!(dry_run, Some(DryRunFormat::JsonPatch));
+
+    let installed: Vec<_> = PluginDiscovery::new()?
+        .discover_all()?
+        .into_iter()
+        .filter(|p| p.marketplace == name && !p.is_dev)
+        .map(|p| p.id)
+        .collect();
+    if !installed.is_empty() && !quiet {
+        println!(
+            "Warning: {} installed plugin{} from {} will no longer receive updates:",
+            installed.len(),
+            if installed.len() == 1 { "" } else { "s" },
+            name
+        );
+        for id in &installed {
+            println!("  {}", id);
+        }
+    }
+
+    if let Some(format) = dry_run {
+        if let (false, Some(checkout)) = (quiet, planned.removal()) {
+            println!("Would remove {}", checkout.display());
+        }
+        return print_plan(&planned.plan, format);
+    }
+    if !installed.is_empty() && !yes {
+        println!("Pass --yes to remove {} anyway.", name);
+        std::process::exit(1);
+    }
+
+    service.apply_marketplace(&planned)?;
+    println!("Removed marketplace {}", name);
+    if let Some(checkout) = planned.removal() {
+        println!("Deleted {}", checkout.display());
+    } else if planned.entry.install_location.exists() {
+        println!("Left {} in place", planned.entry.install_location.display());
+    }
+    Ok(())
+}
+
+fn list_marketplaces() -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let marketplaces = discovery.marketplaces();
+    let pending = discovery.pending_marketplaces();
+    if marketplaces.is_empty() && pending.is_empty() {
+        println!("No marketplaces. Register one with: ccpm marketplace add <source>");
+        return Ok(());
+    }
+
+    println!(
+        "{:<25} {:<10} {:<45} {:<12} LAST UPDATED",
+        "NAME", "SOURCE", "LOCATION", "AUTO-UPDATE"
+    );
+    println!("{}", "-".repeat(110));
+    for (name, entry) in &marketplaces {
+        println!(
+            "{:<25} {:<10} {:<45} {:<12} {}",
+            name,
+            entry.source.kind(),
+            entry.source.location(),
+            if entry.auto_update_enabled() {
+                "on"
+            } else {
+                "off"
+            },
+            format_timestamp(&entry.last_updated)
+        );
+    }
+    for marketplace in &pending {
+        println!(
+            "{:<25} {:<10} {:<45} {:<12} pending",
+            marketplace.name,
+            marketplace.source.kind(),
+            marketplace.source.location(),
+            "-"
+        );
+    }
+    if !pending.is_empty() {
+        println!();
+        println!(
+            "Pending marketplaces are declared in this project's settings. Add them with: ccpm marketplace add --pending"
+        );
+    }
+    Ok(())
+}
+
+fn marketplace_info(name: &str) -> Result<()> {
+    let Some(summary) = PluginDiscovery::new()?
+        .marketplace_summaries()
+        .into_iter()
+        .find(|m| m.name == name)
+    else {
+        println!("Marketplace not found: {}", name);
+        std::process::exit(1);
+    };
+    let entry = &summary.entry;
+
+    println!("Name:        {}", name);
+    println!("Source:      {}", entry.source);
+    println!("Location:    {}", entry.install_location.display());
+    if let Some(ref head) = summary.head {
+        println!("Commit:      {}", short_commit(head));
+    }
+    println!(
+        "Auto-update: {}",
+        if entry.auto_update_enabled() {
+            "on"
+        } else {
+            "off"
+        }
+    );
+    println!("Updated:     {}", format_timestamp(&entry.last_updated));
+
+    let Some(ref catalog) = summary.catalog else {
+        println!();
+        println!("No catalog at {}", entry.install_location.display());
+        return Ok(());
+    };
+    println!();
+    println!("Plugins ({}):", catalog.plugins.len());
+    for plugin in &catalog.plugins {
+        let marker = if summary.is_installed(&plugin.name) {
+            "[I]"
+        } else {
+            "   "
+        };
+        match &plugin.description {
+            Some(description) => println!("  {} {} - {}", marker, plugin.name, description),
+            None => println!("  {} {}", marker, plugin.name),
+        }
+    }
+    Ok(())
+}
+
+fn list_dev_plugins() -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins: Vec<_> = discovery
+        .discover_all()?
+        .into_iter()
+        .filter(|p| p.is_dev)
+        .collect();
+
+    if plugins.is_empty() {
+        println!("No dev plugins. Register one with: ccpm dev add <path>");
+        return Ok(());
+    }
+
+    for plugin in &plugins {
+        let errors = plugin.error_count();
+        let warnings = plugin.diagnostics.len() - errors;
+        let path = plugin
+            .install_path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let status = if plugin.diagnostics.is_empty() {
+            "ok".to_string()
+        } else {
+            format!(
+                "{} error{}, {} warning{}",
+                errors,
+                if errors == 1 { "" } else { "s" },
+                warnings,
+                if warnings == 1 { "" } else { "s" }
+            )
+        };
+        println!("[D] {} {} ({})", plugin.name, path, status);
+        for diagnostic in &plugin.diagnostics {
+            println!("    {}", diagnostic);
+        }
+    }
+    Ok(())
+}
+
+fn budget(all: bool) -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+
+    let mut ranked: Vec<_> = plugins
+        .iter()
+        .filter(|p| all || p.is_active())
+        .map(|p| (p, ContextCost::of(&p.contents)))
+        .collect();
+    ranked.sort_by_key(|(p, cost)| (std::cmp::Reverse(cost.bytes()), p.id.clone()));
+
+    if ranked.is_empty() {
+        println!("No plugins enabled in this project.");
+        return Ok(());
+    }
+
+    println!("{:<35} {:>9} {:>9}  CONTENTS", "PLUGIN", "BYTES", "~TOKENS");
+    println!("{}", "-".repeat(90));
+    for (plugin, cost) in &ranked {
+        println!(
+            "{:<35} {:>9} {:>9}  {}",
+            plugin.id,
+            cost.bytes(),
+            cost.tokens(),
+            plugin.contents.summary()
+        );
+    }
+
+    let total: ContextCost = ranked.iter().map(|(_, cost)| *cost).sum();
+    println!("{}", "-".repeat(90));
+    println!(
+        "{:<35} {:>9} {:>9}",
+        format!(
+            "Total ({} plugin{})",
+            ranked.len(),
+            if ranked.len() == 1 { "" } else { "s" }
+        ),
+        total.bytes(),
+        format_tokens(total.tokens())
+    );
+    println!(
+        "\nEstimate: names and descriptions of commands, agents and skills, plus {} bytes per MCP server.",
+        MCP_SERVER_BYTES
+    );
+
+    Ok(())
+}
+
+fn outdated(all: bool, format: OutputFormat) -> Result<()> {
+    let updates: Vec<_> = PluginDiscovery::new()?
+        .check_updates()
+        .into_iter()
+        .filter(|u| all || u.status.has_update())
+        .collect();
+
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&updates)?);
+        return Ok(());
+    }
+
+    if updates.is_empty() {
+        println!("All plugins are up to date.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<35} {:<12} {:<20} STATUS",
+        "PLUGIN", "INSTALLED", "AVAILABLE"
+    );
+    println!("{}", "-".repeat(90));
+    for update in &updates {
+        println!(
+            "{:<35} {:<12} {:<20} {}",
+            update.plugin_id,
+            update.installed_version,
+            update.available_display(),
+            match (&update.pin, update.is_held()) {
+                (Some(pin), true) => format!("{}, pinned to {}", update.status, pin),
+                (Some(pin), false) => format!("{} (pinned to {})", update.status, pin),
+                (None, _) => update.status.to_string(),
+            }
+        );
+    }
+    Ok(())
+}
+
+fn update(
+    plugin_ids: &[String],
+    all: bool,
+    no_fetch: bool,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let service = PluginService::new()?;
+    let discovery = PluginDiscovery::new()?;
+    // Messages would break a json-patch on stdout
+    let quiet = matches!(dry_run, Some(DryRunFormat::JsonPatch));
+
+    if !no_fetch && dry_run.is_none() {
+        let mut marketplaces: Vec<String> = if all {
+            discovery
+                .marketplaces()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        } else {
+            plugin_ids
+                .iter()
+                .filter_map(|id| id.split_once('@'))
+                .map(|(_, marketplace)| marketplace.to_string())
+                .collect()
+        };
+        marketplaces.sort();
+        marketplaces.dedup();
+        for name in &marketplaces {
+            match service.refresh_marketplace(name) {
+                Ok(Some(head)) => println!("Fetched {} ({})", name, short_commit(&head)),
+                Ok(None) => {}
+                Err(e) => println!("Could not fetch {}: {}", name, e),
+            }
+        }
+    }
+
+    let updates = discovery.check_updates();
+    let selected: Vec<&PluginUpdate> = if all {
+        updates.iter().filter(|u| u.status.has_update()).collect()
+    } else {
+        let mut selected = Vec::new();
+        for id in plugin_ids {
+            match updates.iter().find(|u| &u.plugin_id == id) {
+                Some(update) => selected.push(update),
+                None => {
+                    println!("Plugin '{}' not found.", id);
+                    std::process::exit(1);
+                }
+            }
+        }
+        selected
+    };
+
+    let (mut updated, mut held, mut failed) = (0, 0, 0);
+    for update in selected {
+        match update.status {
+            UpdateStatus::UpToDate if quiet => continue,
+            UpdateStatus::UpToDate => {
+                println!(
+                    "{} is up to date ({}).",
+                    update.plugin_id, update.installed_version
+                );
+                continue;
+            }
+            UpdateStatus::Unknown if quiet => continue,
+            UpdateStatus::Unknown => {
+                println!(
+                    "{}: no update information (not in its marketplace catalog, or the marketplace is not checked out).",
+                    update.plugin_id
+                );
+                continue;
+            }
+            UpdateStatus::NewerVersion | UpdateStatus::DifferentCommit => {}
+        }
+        if let (true, Some(pin)) = (update.is_held(), &update.pin) {
+            if !quiet {
+                println!(
+                    "Skipped {}: pinned to {} ({} available)",
+                    update.plugin_id,
+                    pin,
+                    update.available_display()
+                );
+            }
+            held += 1;
+            continue;
+        }
+        if let Some(format) = dry_run {
+            match service.plan_update(update) {
+                Ok(planned) => {
+                    if !quiet {
+                        println!(
+                            "Would update {}: {} -> {}",
+                            update.plugin_id,
+                            update.installed_version,
+                            update.available_display()
+                        );
+                        if !planned.is_current() && !planned.target.exists() {
+                            println!(
+                                "Would copy {} to {}",
+                                planned.source_dir.display(),
+                                planned.target.display()
+                            );
+                        }
+                        for stale in planned.stale_caches() {
+                            println!("Would remove {}", stale.display());
+                        }
+                    }
+                    print_plan(&planned.plan, format)?;
+                    updated += 1;
+                }
+                Err(e) => {
+                    println!("Failed to update {}: {}", update.plugin_id, e);
+                    failed += 1;
+                }
+            }
+            continue;
+        }
+        match service.update_plugin(update) {
+            Ok(_) => {
+                println!(
+                    "Updated {}: {} -> {}",
+                    update.plugin_id,
+                    update.installed_version,
+                    update.available_display()
+                );
+                updated += 1;
+            }
+            Err(e) => {
+                println!("Failed to update {}: {}", update.plugin_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if all && updated + held + failed == 0 && !quiet {
+        println!("All plugins are up to date.");
+    }
+    if updated > 0 && dry_run.is_none() {
+        println!("\nRestart Claude Code to load the updated plugins.");
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn changelog(plugin_id: &str) -> Result<()> {
+    let changelog = PluginDiscovery::new()?.changelog(plugin_id)?;
+    let version = |version: &str, commit: Option<&str>| match commit {
+        Some(commit) => format!("{} ({})", version, short_commit(commit)),
+        None => version.to_string(),
+    };
+    println!(
+        "{}: {} -> {}",
+        changelog.plugin_id,
+        version(
+            &changelog.installed_version,
+            Some(&changelog.installed_commit)
+        ),
+        version(
+            changelog
+                .available_version
+                .as_deref()
+                .unwrap_or(&changelog.installed_version),
+            changelog.available_commit.as_deref()
+        )
+    );
+
+    if changelog.commits.is_empty() && changelog.changes.is_empty() {
+        println!("\nNo changes since the installed commit.");
+        return Ok(());
+    }
+
+    if !changelog.commits.is_empty() {
+        println!("\nCommits ({}):", changelog.commits.len());
+        for commit in &changelog.commits {
+            println!(
+                "  {}  {}  {:<16} {}",
+                short_commit(&commit.sha),
+                commit.date,
+                commit.author,
+                commit.subject
+            );
+        }
+    }
+
+    if !changelog.changes.is_empty() {
+        println!("\nChanges:");
+        for change in &changelog.changes {
+            println!(
+                "  {} {} {}",
+                change.change.symbol(),
+                change.component,
+                change.name
+            );
+            for risk in &change.risks {
+                println!("      ! {}: {}", risk.kind, risk.evidence);
+            }
+        }
+    }
+    if changelog.has_risky_hooks() {
+        println!("\nReview the flagged hooks before updating: they run without asking.");
+    }
+    Ok(())
+}
+
+fn rollback(plugin_id: &str, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let service = PluginService::new()?;
+    let plan = service.plan_rollback(plugin_id)?;
+    if let Some(format) = dry_run {
+        return print_plan(&plan, format);
+    }
+    service.apply(&plan)?;
+
+    let restored = PluginDiscovery::new()?
+        .check_updates()
+        .into_iter()
+        .find(|u| u.plugin_id == plugin_id);
+    if let Some(restored) = restored {
+        println!(
+            "Rolled back {} to {}",
+            plugin_id,
+            match &restored.installed_commit {
+                Some(commit) =>
+                    format!("{} ({})", restored.installed_version, short_commit(commit)),
+                None => restored.installed_version.clone(),
+            }
+        );
+        if restored.pin.is_none() {
+            println!(
+                "Pin it with `ccpm pin {}` to keep updates from replacing it.",
+                plugin_id
+            );
+        }
+    }
+    println!("Restart Claude Code to load it.");
+    Ok(())
+}
+
+fn list_hooks(plugin_id: Option<&str>, only_risky: bool) -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+
+    if let Some(id) = plugin_id {
+        if !plugins.iter().any(|p| p.id == id) {
+            println!("Plugin '{}' not found.", id);
+            return Ok(());
+        }
+    }
+
+    let mut shown = 0;
+    for plugin in plugins
+        .iter()
+        .filter(|p| plugin_id.map_or(true, |id| p.id == id))
+    {
+        let hooks: Vec<_> = plugin
+            .contents
+            .hooks
+            .iter()
+            .filter(|h| !only_risky || h.is_risky())
+            .collect();
+        if hooks.is_empty() {
+            continue;
+        }
+
+        if shown > 0 {
+            println!();
+        }
+        println!(
+            "{} ({})",
+            plugin.id,
+            if plugin.is_active() {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        for hook in hooks {
+            let event = match hook.matcher {
+                Some(ref matcher) => format!("{} [{}]", hook.event, matcher),
+                None => hook.event.clone(),
+            };
+            println!(
+                "  {:<28} {}",
+                event,
+                hook.command.as_deref().unwrap_or(&hook.hook_type)
+            );
+            for risk in &hook.risks {
+                println!("  {:<28} ! {}: {}", "", risk.kind, risk.evidence);
+            }
+            shown += 1;
+        }
+    }
+
+    if shown == 0 {
+        println!(
+            "{}",
+            if only_risky {
+                "No risky hooks found."
+            } else {
+                "No hooks found."
+            }
+        );
+    }
+
+    Ok(())
+}
+
+fn mcp_check(plugin_id: &str, server: Option<&str>, timeout: u64) -> Result<()> {
+    let discovery = PluginDiscovery::new()?;
+    let plugins = discovery.discover_all()?;
+
+    let Some(plugin) = plugins.iter().find(|p| p.id == plugin_id) else {
+        println!("Plugin '{}' not found.", plugin_id);
+        return Ok(());
+    };
+    let Some(ref root) = plugin.install_path else {
+        println!("Plugin '{}' is not installed.", plugin_id);
+        return Ok(());
+    };
+
+    let servers: Vec<_> = plugin
+        .contents
+        .mcp_servers
+        .iter()
+        .filter(|s| server.map_or(true, |name| s.name == name))
+        .collect();
+    if servers.is_empty() {
+        match server {
+            Some(name) => println!("{} has no MCP server named '{}'.", plugin_id, name),
+            None => println!("{} declares no MCP servers.", plugin_id),
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (i, item) in servers.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if item.transport() != "stdio" {
+            println!("{} ({}): {}", item.name, item.transport(), item.target());
+            println!("  skipped: only stdio servers are checked");
+            continue;
+        }
+
+        let report = check_stdio_server(item, root, Duration::from_secs(timeout));
+        println!("{} (stdio): {}", item.name, report.command_line);
+        if let Some(ref error) = report.error {
+            failed += 1;
+            println!("  FAILED: {}", error);
+        } else {
+            println!(
+                "  ok: {} {} (protocol {})",
+                report.server_name.as_deref().unwrap_or("unnamed server"),
+                report.server_version.as_deref().unwrap_or("?"),
+                report.protocol_version.as_deref().unwrap_or("?")
+            );
+            println!(
+                "  tools ({}): {}",
+                report.tools.len(),
+                report.tools.join(", ")
+            );
+        }
+        if !report.missing_env.is_empty() {
+            println!("  missing env: {}", report.missing_env.join(", "));
+        }
+        if report.error.is_some() {
+            for line in &report.stderr {
+                println!("  stderr: {}", line);
+            }
+        }
+    }
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use ccpm::app::{App, AppMode};
-use ccpm::cli::{run_command, Cli};
+use ccpm::app::{App, AppMode, Tab};
+use ccpm::cli::{housekeeping, run_command, Cli, Commands};
+use ccpm::plugin::BisectVerdict;
 use ccpm::ui;
 use clap::Parser;
 use color_eyre::Result;
@@ -10,11 +11,22 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 use std::io;
+use std::time::Duration;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
 
     let cli = Cli::parse();
+    // `ccpm gc` runs the same cleanup itself and reports when there was nothing to do.
+    // A dry run writes nothing, so it leaves the cleanup for later.
+    let skip = match &cli.command {
+        Some(Commands::Gc { .. }) => true,
+        Some(cmd) => cmd.is_dry_run(),
+        None => false,
+    };
+    if !skip {
+        housekeeping();
+    }
 
     match cli.command {
         Some(cmd) => run_command(cmd),
@@ -56,6 +68,12 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
     loop {
         terminal.draw(|f| ui::render(f, app))?;
 
+        // Wake up every second so expiry countdowns stay current
+        if !event::poll(Duration::from_secs(1))? {
+            app.tick();
+            continue;
+        }
+
         if let Event::Key(key) = event::read()? {
             // Handle Ctrl+C globally
             if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
@@ -63,11 +81,18 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
             }
 
             match app.mode {
+                AppMode::Normal if app.tab == Tab::McpServers => handle_mcp_tab(app, key.code),
+                AppMode::Normal if app.tab == Tab::Marketplaces => {
+                    handle_marketplaces_tab(app, key.code)
+                }
                 AppMode::Normal => handle_normal_mode(app, key.code),
                 AppMode::Search => handle_search_mode(app, key.code),
                 AppMode::Help => handle_help_mode(app, key.code),
                 AppMode::Confirm(_) => handle_confirm_mode(app, key.code),
                 AppMode::DetailModal => handle_detail_modal_mode(app, key.code),
+                AppMode::EnableFor => handle_enable_for_mode(app, key.code),
+                AppMode::Bisect => handle_bisect_mode(app, key.code),
+                AppMode::MarketplaceModal => handle_marketplace_modal_mode(app, key.code),
             }
         }
 
@@ -87,10 +112,12 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
 
         // Plugin actions
         KeyCode::Char('e') => app.enable_selected_plugin(),
+        KeyCode::Char('t') => app.start_enable_for(),
         KeyCode::Char('d') => app.disable_selected_plugin(),
         KeyCode::Char(' ') => app.toggle_selected_plugin(),
         KeyCode::Enter => app.show_detail_modal(),
         KeyCode::Char('x') => app.confirm_remove(),
+        KeyCode::Char('B') => app.open_bisect(),
 
         // Filtering
         KeyCode::Char('s') => app.cycle_scope_filter(),
@@ -104,7 +131,16 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
                     e
                 )));
             } else {
-                app.message = Some(ccpm::app::StatusMessage::info("Plugins reloaded"));
+                let errors = app.dev_error_count();
+                app.message = Some(if errors > 0 {
+                    ccpm::app::StatusMessage::error(format!(
+                        "Plugins reloaded; {} error{} in dev plugins",
+                        errors,
+                        if errors == 1 { "" } else { "s" }
+                    ))
+                } else {
+                    ccpm::app::StatusMessage::info("Plugins reloaded")
+                });
             }
         }
 
@@ -112,7 +148,62 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
         KeyCode::Char('?') => app.show_help(),
         KeyCode::Char('q') => app.quit(),
         KeyCode::Esc => app.clear_search(),
+        KeyCode::Tab => app.next_tab(),
+
+        _ => {}
+    }
+}
+
+fn handle_mcp_tab(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.move_mcp_selection(1),
+        KeyCode::Char('k') | KeyCode::Up => app.move_mcp_selection(-1),
+        KeyCode::Char(' ') => app.toggle_selected_mcp_approval(),
+        KeyCode::Char('r') => {
+            if let Err(e) = app.reload_plugins() {
+                app.message = Some(ccpm::app::StatusMessage::error(format!(
+                    "Reload failed: {}",
+                    e
+                )));
+            } else {
+                app.message = Some(ccpm::app::StatusMessage::info("MCP servers reloaded"));
+            }
+        }
+        KeyCode::Tab => app.next_tab(),
+        KeyCode::Char('?') => app.show_help(),
+        KeyCode::Char('q') => app.quit(),
+        _ => {}
+    }
+}
+
+fn handle_marketplaces_tab(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.move_marketplace_selection(1),
+        KeyCode::Char('k') | KeyCode::Up => app.move_marketplace_selection(-1),
+        KeyCode::Char('u') | KeyCode::Char(' ') => app.toggle_selected_auto_update(),
+        KeyCode::Char('a') => app.add_selected_pending_marketplace(),
+        KeyCode::Enter => app.show_marketplace_modal(),
+        KeyCode::Char('r') => {
+            if let Err(e) = app.reload_plugins() {
+                app.message = Some(ccpm::app::StatusMessage::error(format!(
+                    "Reload failed: {}",
+                    e
+                )));
+            } else {
+                app.message = Some(ccpm::app::StatusMessage::info("Marketplaces reloaded"));
+            }
+        }
+        KeyCode::Tab => app.next_tab(),
+        KeyCode::Char('?') => app.show_help(),
+        KeyCode::Char('q') => app.quit(),
+        _ => {}
+    }
+}
 
+fn handle_marketplace_modal_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.hide_marketplace_modal(),
+        KeyCode::Char('u') | KeyCode::Char(' ') => app.toggle_selected_auto_update(),
         _ => {}
     }
 }
@@ -126,6 +217,26 @@ fn handle_search_mode(app: &mut App, key: KeyCode) {
     }
 }
 
+fn handle_enable_for_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Enter => app.submit_enable_for(),
+        KeyCode::Esc => app.cancel_enable_for(),
+        KeyCode::Backspace => app.delete_duration_char(),
+        KeyCode::Char(c) => app.append_duration_char(c),
+        _ => {}
+    }
+}
+
+fn handle_bisect_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('g') => app.mark_bisect(BisectVerdict::Good),
+        KeyCode::Char('b') => app.mark_bisect(BisectVerdict::Bad),
+        KeyCode::Char('r') => app.reset_bisect(),
+        KeyCode::Esc | KeyCode::Char('q') => app.close_bisect(),
+        _ => {}
+    }
+}
+
 fn handle_help_mode(app: &mut App, key: KeyCode) {
     match key {
         KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => app.hide_help(),
@@ -148,6 +259,7 @@ fn handle_detail_modal_mode(app: &mut App, key: KeyCode) {
         KeyCode::Char(' ') => app.toggle_selected_plugin(),
         KeyCode::Char('e') => app.enable_selected_plugin(),
         KeyCode::Char('d') => app.disable_selected_plugin(),
+        KeyCode::Char('m') => app.check_selected_mcp_servers(),
         _ => {}
     }
 }
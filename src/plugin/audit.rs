@@ -0,0 +1,371 @@
+//! Static checks for hook commands.
+//!
+//! Hooks run arbitrary shell commands with the user's privileges whenever Claude
+//! starts a session, runs a tool, and so on. These heuristics flag the patterns worth
+//! a second look before enabling a plugin. They prompt a review; they are not a sandbox.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskKind {
+    PipeToShell,
+    RecursiveDelete,
+    WriteOutsideProject,
+    Network,
+}
+
+impl std::fmt::Display for RiskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskKind::PipeToShell => write!(f, "runs a downloaded script"),
+            RiskKind::RecursiveDelete => write!(f, "recursive delete"),
+            RiskKind::WriteOutsideProject => write!(f, "writes outside the project"),
+            RiskKind::Network => write!(f, "network access"),
+        }
+    }
+}
+
+/// A risky pattern found in a hook command or in a script it runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RiskFlag {
+    pub kind: RiskKind,
+    /// The offending command, or `script.sh:3: line` when found in a script
+    pub evidence: String,
+}
+
+const NETWORK_TOOLS: &[&str] = &[
+    "curl", "wget", "nc", "ncat", "netcat", "socat", "ssh", "scp", "sftp", "rsync", "telnet", "ftp",
+];
+
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "ksh", "fish", "python", "python3", "node", "perl", "ruby",
+];
+
+/// Redirect targets that stay inside the project, the plugin or temp space
+const SAFE_TARGET_PREFIXES: &[&str] = &[
+    "/dev/",
+    "/tmp/",
+    "$CLAUDE_PROJECT_DIR",
+    "${CLAUDE_PROJECT_DIR}",
+    "$CLAUDE_PLUGIN_ROOT",
+    "${CLAUDE_PLUGIN_ROOT}",
+    "$TMPDIR",
+    "${TMPDIR}",
+];
+
+/// Largest script that is read for auditing
+const MAX_SCRIPT_BYTES: u64 = 1024 * 1024;
+
+/// Check a hook command. Scripts it runs from the plugin directory
+/// (`${CLAUDE_PLUGIN_ROOT}/...`) are read and checked line by line too.
+/// Returns at most one flag per kind, most severe first.
+pub fn audit_hook_command(command: &str, plugin_root: &Path) -> Vec<RiskFlag> {
+    let mut flags: Vec<RiskFlag> = check_line(command)
+        .into_iter()
+        .map(|kind| RiskFlag {
+            kind,
+            evidence: command.to_string(),
+        })
+        .collect();
+
+    for script in plugin_scripts(command, plugin_root) {
+        let Ok(text) = fs::read_to_string(&script) else {
+            continue;
+        };
+        let name = script.strip_prefix(plugin_root).unwrap_or(&script);
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            for kind in check_line(line) {
+                flags.push(RiskFlag {
+                    kind,
+                    evidence: format!("{}:{}: {}", name.display(), index + 1, line),
+                });
+            }
+        }
+    }
+
+    flags.sort_by_key(|f| f.kind);
+    flags.dedup_by_key(|f| f.kind);
+    flags
+}
+
+/// Risk kinds found in one shell line
+fn check_line(line: &str) -> Vec<RiskKind> {
+    let mut kinds = Vec::new();
+
+    let separated = line.replace("||", ";").replace("&&", ";");
+    for command in separated.split([';', '\n']) {
+        let pipeline: Vec<Vec<&str>> = command.split('|').map(raw_words).collect();
+
+        for (i, segment) in pipeline.iter().enumerate() {
+            let words: Vec<String> = segment.iter().map(|w| program(w)).collect();
+
+            if words.iter().any(|w| NETWORK_TOOLS.contains(&w.as_str())) {
+                kinds.push(RiskKind::Network);
+
+                // curl ... | sh
+                let next_runs_shell = pipeline
+                    .get(i + 1)
+                    .and_then(|next| first_program(next))
+                    .is_some_and(|p| INTERPRETERS.contains(&p.as_str()));
+                if next_runs_shell {
+                    kinds.push(RiskKind::PipeToShell);
+                }
+            }
+
+            // bash <(curl ...) / sh -c "$(wget ...)"
+            let runs_shell =
+                first_program(segment).is_some_and(|p| INTERPRETERS.contains(&p.as_str()));
+            let substitutes_download = segment.iter().any(|w| {
+                let w = w.trim_start_matches(['"', '\'']);
+                (w.starts_with("<(") || w.starts_with("$("))
+                    && NETWORK_TOOLS.contains(&program(w).as_str())
+            });
+            if runs_shell && substitutes_download {
+                kinds.push(RiskKind::PipeToShell);
+            }
+
+            if is_recursive_rm(&words, segment) {
+                kinds.push(RiskKind::RecursiveDelete);
+            }
+
+            if let Some(pos) = words.iter().position(|w| w == "tee") {
+                let outside = segment[pos + 1..]
+                    .iter()
+                    .filter(|w| !w.starts_with('-'))
+                    .any(|w| is_outside_project(unquote(w)));
+                if outside {
+                    kinds.push(RiskKind::WriteOutsideProject);
+                }
+            }
+        }
+    }
+
+    if redirect_targets(line)
+        .iter()
+        .any(|target| is_outside_project(target))
+    {
+        kinds.push(RiskKind::WriteOutsideProject);
+    }
+
+    kinds.sort();
+    kinds.dedup();
+    kinds
+}
+
+fn raw_words(segment: &str) -> Vec<&str> {
+    segment.split_whitespace().collect()
+}
+
+/// Program name of a word: quotes, substitutions and directories stripped
+fn program(word: &str) -> String {
+    let word = word.trim_matches(|c: char| "\"'`()<>${}&".contains(c));
+    word.rsplit('/').next().unwrap_or(word).to_string()
+}
+
+/// First program of a pipeline segment, skipping `sudo`, `env` and `VAR=value`
+fn first_program(segment: &[&str]) -> Option<String> {
+    segment
+        .iter()
+        .map(|w| program(w))
+        .find(|p| !p.is_empty() && p != "sudo" && p != "env" && !p.contains('='))
+}
+
+fn is_recursive_rm(words: &[String], segment: &[&str]) -> bool {
+    let Some(pos) = words.iter().position(|w| w == "rm") else {
+        return false;
+    };
+    segment[pos + 1..].iter().any(|arg| {
+        *arg == "--recursive"
+            || (arg.starts_with('-') && !arg.starts_with("--") && arg.contains(['r', 'R']))
+    })
+}
+
+/// Targets of `>` and `>>` redirects, ignoring fd duplication (`2>&1`) and quoted text
+fn redirect_targets(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut targets = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' && !matches!(i.checked_sub(1).map(|p| chars[p]), Some('-' | '=')) => {
+                let mut j = i + 1;
+                if chars.get(j) == Some(&'>') {
+                    j += 1;
+                }
+                if chars.get(j) == Some(&'&') {
+                    i = j + 1;
+                    continue;
+                }
+                while chars.get(j).is_some_and(|c| c.is_whitespace()) {
+                    j += 1;
+                }
+                let start = j;
+                while chars
+                    .get(j)
+                    .is_some_and(|c| !c.is_whitespace() && !";|&)".contains(*c))
+                {
+                    j += 1;
+                }
+                let target: String = chars[start..j].iter().collect();
+                if !target.is_empty() {
+                    targets.push(unquote(&target).to_string());
+                }
+                i = j;
+                continue;
+            }
+            None => {}
+        }
+        i += 1;
+    }
+
+    targets
+}
+
+/// Hooks run in the project directory, so relative paths stay inside it
+fn is_outside_project(target: &str) -> bool {
+    if SAFE_TARGET_PREFIXES.iter().any(|p| target.starts_with(p)) {
+        return false;
+    }
+    target.starts_with('/')
+        || target.starts_with('~')
+        || target.starts_with("$HOME")
+        || target.starts_with("${HOME}")
+        || target.starts_with("../")
+}
+
+fn unquote(word: &str) -> &str {
+    word.trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Files inside the plugin directory that the command refers to via `${CLAUDE_PLUGIN_ROOT}`
+fn plugin_scripts(command: &str, plugin_root: &Path) -> Vec<PathBuf> {
+    plugin_script_refs(command, plugin_root)
+        .into_iter()
+        .filter(|path| fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_SCRIPT_BYTES))
+        .collect()
+}
+
+/// Paths under the plugin directory named via `${CLAUDE_PLUGIN_ROOT}`, whether they exist or not
+pub(crate) fn plugin_script_refs(command: &str, plugin_root: &Path) -> Vec<PathBuf> {
+    let root = plugin_root.to_string_lossy();
+    command
+        .split_whitespace()
+        .map(unquote)
+        .filter(|w| w.contains("CLAUDE_PLUGIN_ROOT"))
+        .map(|w| {
+            PathBuf::from(
+                w.replace("${CLAUDE_PLUGIN_ROOT}", &root)
+                    .replace("$CLAUDE_PLUGIN_ROOT", &root),
+            )
+        })
+        .filter(|path| path.starts_with(plugin_root))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn kinds(command: &str) -> Vec<RiskKind> {
+        audit_hook_command(command, Path::new("/nonexistent"))
+            .into_iter()
+            .map(|f| f.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_harmless_commands() {
+        assert!(kinds("echo done").is_empty());
+        assert!(kinds("npx prettier --write \"$CLAUDE_PROJECT_DIR/src\" 2>&1").is_empty());
+        assert!(kinds("jq -r '.tool_input.command' > /dev/null").is_empty());
+        assert!(kinds("echo \"a > /etc/b\" >> log/hooks.txt").is_empty());
+        assert!(kinds("rm -f build/out.txt").is_empty());
+    }
+
+    #[test]
+    fn test_network_and_pipe_to_shell() {
+        assert_eq!(
+            kinds("curl -s https://example.com/notify"),
+            vec![RiskKind::Network]
+        );
+        assert_eq!(
+            kinds("curl -fsSL https://x.sh/install | sudo bash"),
+            vec![RiskKind::PipeToShell, RiskKind::Network]
+        );
+        assert_eq!(
+            kinds("bash <(wget -qO- https://x.sh)"),
+            vec![RiskKind::PipeToShell, RiskKind::Network]
+        );
+        assert_eq!(
+            kinds("sh -c \"$(/usr/bin/curl -s https://x.sh)\""),
+            vec![RiskKind::PipeToShell, RiskKind::Network]
+        );
+    }
+
+    #[test]
+    fn test_recursive_delete() {
+        assert_eq!(
+            kinds("rm -rf node_modules"),
+            vec![RiskKind::RecursiveDelete]
+        );
+        assert_eq!(
+            kinds("cd /x && rm -R -f ."),
+            vec![RiskKind::RecursiveDelete]
+        );
+        assert_eq!(
+            kinds("rm --recursive dist"),
+            vec![RiskKind::RecursiveDelete]
+        );
+    }
+
+    #[test]
+    fn test_writes_outside_project() {
+        assert_eq!(
+            kinds("echo $TOOL >> ~/.bashrc"),
+            vec![RiskKind::WriteOutsideProject]
+        );
+        assert_eq!(
+            kinds("date>/etc/last-run"),
+            vec![RiskKind::WriteOutsideProject]
+        );
+        assert_eq!(
+            kinds("cat input | tee -a \"$HOME/log.txt\""),
+            vec![RiskKind::WriteOutsideProject]
+        );
+        assert!(kinds("echo ok > /tmp/hook.log").is_empty());
+        assert!(kinds("echo ok > \"${CLAUDE_PROJECT_DIR}/.log\"").is_empty());
+    }
+
+    #[test]
+    fn test_scans_plugin_scripts() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("hooks")).unwrap();
+        fs::write(
+            temp.path().join("hooks/check.sh"),
+            "#!/bin/sh\n# curl is mentioned in a comment\nset -e\nwget -q https://x/payload\n",
+        )
+        .unwrap();
+
+        let flags = audit_hook_command("\"${CLAUDE_PLUGIN_ROOT}/hooks/check.sh\"", temp.path());
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].kind, RiskKind::Network);
+        assert_eq!(
+            flags[0].evidence,
+            "hooks/check.sh:4: wget -q https://x/payload"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Bisecting the enabled plugins to find the one causing a problem.
+//!
+//! `ccpm bisect start` saves the project's local settings and the plugins active
+//! in it. Each step enables the first half of the remaining suspects in local
+//! scope and disables the rest; `good` clears that half, `bad` keeps only it.
+//! Assumes a single culprit, so a problem caused by two plugins together ends
+//! on one of them. A suspect that was never in a bad step gets one more step on
+//! its own, and the bisect ends without a culprit if that is good too. The
+//! state lives in `~/.config/ccpm/bisect.json` so the CLI and the TUI drive the
+//! same session; it names the settings file by absolute path, so steps land in
+//! the project the bisect started in wherever they are run from.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BisectVerdict {
+    /// The problem did not occur with the plugins under test
+    Good,
+    /// The problem occurred with the plugins under test
+    Bad,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectState {
+    /// Local settings file the steps are written to
+    pub settings_path: PathBuf,
+    /// Its content before the bisect; None if it did not exist
+    pub original: Option<String>,
+    /// Plugins active when the bisect started
+    pub plugins: Vec<String>,
+    /// Plugins that may still be the culprit
+    pub suspects: Vec<String>,
+    /// Steps answered so far
+    #[serde(default)]
+    pub steps: usize,
+    /// Some step was marked bad, so the culprit is among the suspects
+    #[serde(default)]
+    pub found_bad: bool,
+    pub started_at: String,
+}
+
+impl BisectState {
+    pub fn new(
+        settings_path: PathBuf,
+        original: Option<String>,
+        plugins: Vec<String>,
+        started_at: String,
+    ) -> Self {
+        Self {
+            settings_path,
+            original,
+            suspects: plugins.clone(),
+            plugins,
+            steps: 0,
+            found_bad: false,
+            started_at,
+        }
+    }
+
+    /// Plugins enabled in the current step: the first half of the suspects
+    pub fn testing(&self) -> &[String] {
+        let len = self.suspects.len();
+        &self.suspects[..(len / 2).max(1).min(len)]
+    }
+
+    /// Narrow the suspects by the result of the current step
+    pub fn record(&mut self, verdict: BisectVerdict) {
+        if self.is_done() {
+            return;
+        }
+        let half = self.testing().len();
+        match verdict {
+            BisectVerdict::Bad => {
+                self.suspects.truncate(half);
+                self.found_bad = true;
+            }
+            BisectVerdict::Good => {
+                self.suspects.drain(..half);
+            }
+        }
+        self.steps += 1;
+    }
+
+    /// The remaining suspect once only one is left and a step was bad
+    pub fn culprit(&self) -> Option<&str> {
+        match self.suspects.as_slice() {
+            [only] if self.found_bad => Some(only),
+            _ => None,
+        }
+    }
+
+    /// The culprit is found, or every suspect was cleared
+    pub fn is_done(&self) -> bool {
+        self.culprit().is_some() || self.suspects.is_empty()
+    }
+
+    /// Steps still needed to narrow the suspects down to one, plus the step
+    /// confirming it when no step was bad yet
+    pub fn steps_left(&self) -> usize {
+        if self.is_done() {
+            return 0;
+        }
+        let mut left = usize::from(!self.found_bad);
+        let mut n = self.suspects.len();
+        while n > 1 {
+            n -= n / 2;
+            left += 1;
+        }
+        left
+    }
+
+    /// `enabledPlugins` overrides for the current step: testing half on, the
+    /// rest of the plugins active at the start off
+    pub fn overrides(&self) -> Vec<(&str, bool)> {
+        let testing = self.testing();
+        self.plugins
+            .iter()
+            .map(|id| (id.as_str(), testing.contains(id)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(n: usize) -> BisectState {
+        let plugins = (0..n).map(|i| format!("p{}@m", i)).collect();
+        BisectState::new(PathBuf::from("s.json"), None, plugins, String::new())
+    }
+
+    #[test]
+    fn test_bisect_finds_culprit() {
+        // Culprit p4 among 7: every step is bad iff p4 is enabled
+        let mut bisect = state(7);
+        assert_eq!(bisect.steps_left(), 4);
+        while !bisect.is_done() {
+            let bad = bisect.testing().iter().any(|id| id == "p4@m");
+            bisect.record(if bad {
+                BisectVerdict::Bad
+            } else {
+                BisectVerdict::Good
+            });
+        }
+        assert_eq!(bisect.culprit(), Some("p4@m"));
+        assert!(bisect.steps <= 3);
+    }
+
+    #[test]
+    fn test_overrides_cover_all_plugins() {
+        let mut bisect = state(4);
+        bisect.record(BisectVerdict::Good);
+        assert_eq!(
+            bisect.overrides(),
+            vec![
+                ("p0@m", false),
+                ("p1@m", false),
+                ("p2@m", true),
+                ("p3@m", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_good_finds_no_culprit() {
+        let mut bisect = state(3);
+        assert_eq!(bisect.steps_left(), 3);
+        bisect.record(BisectVerdict::Good);
+        bisect.record(BisectVerdict::Good);
+        // The last suspect was never tested alone, so it gets a step of its own
+        assert_eq!(bisect.suspects, vec!["p2@m"]);
+        assert_eq!(bisect.testing(), &["p2@m".to_string()]);
+        assert_eq!(bisect.culprit(), None);
+        assert_eq!(bisect.steps_left(), 1);
+
+        bisect.record(BisectVerdict::Good);
+        assert!(bisect.is_done());
+        assert_eq!(bisect.culprit(), None);
+        assert_eq!(bisect.steps, 3);
+
+        let mut bisect = state(2);
+        bisect.record(BisectVerdict::Good);
+        bisect.record(BisectVerdict::Bad);
+        assert_eq!(bisect.culprit(), Some("p1@m"));
+    }
+
+    #[test]
+    fn test_single_plugin_needs_a_step() {
+        let mut bisect = state(1);
+        assert_eq!(bisect.culprit(), None);
+        assert_eq!(bisect.steps_left(), 1);
+        bisect.record(BisectVerdict::Bad);
+        assert_eq!(bisect.culprit(), Some("p0@m"));
+        assert_eq!(bisect.steps_left(), 0);
+    }
+}
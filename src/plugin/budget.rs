@@ -0,0 +1,140 @@
+//! Rough estimate of how much of Claude's context a plugin takes up.
+//!
+//! Claude sees the name and description of every command, agent and skill of an
+//! enabled plugin, plus the tool schemas of its MCP servers. Descriptions are
+//! measured from the files on disk; tool schemas are only known once a server
+//! runs, so each MCP server counts as a fixed estimate.
+
+use super::{ContentItem, Plugin, PluginContents};
+use serde::Serialize;
+
+/// Approximate bytes per token for English prose and JSON
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Assumed tool schema size of one MCP server (around ten tools)
+pub const MCP_SERVER_BYTES: usize = 6_000;
+
+/// Estimated context footprint in bytes, split by component kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ContextCost {
+    pub commands: usize,
+    pub agents: usize,
+    pub skills: usize,
+    pub mcp_servers: usize,
+}
+
+impl ContextCost {
+    pub fn of(contents: &PluginContents) -> Self {
+        let described = |items: &[ContentItem]| -> usize {
+            items
+                .iter()
+                .map(|i| i.name.len() + i.description.as_deref().map_or(0, str::len))
+                .sum()
+        };
+        Self {
+            commands: described(&contents.commands),
+            agents: described(&contents.agents),
+            skills: described(&contents.skills),
+            mcp_servers: contents.mcp_servers.len() * MCP_SERVER_BYTES,
+        }
+    }
+
+    /// Total cost of the plugins active in the current project
+    pub fn of_active(plugins: &[Plugin]) -> Self {
+        plugins
+            .iter()
+            .filter(|p| p.is_active())
+            .map(|p| Self::of(&p.contents))
+            .sum()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.commands + self.agents + self.skills + self.mcp_servers
+    }
+
+    pub fn tokens(&self) -> usize {
+        (self.bytes() + BYTES_PER_TOKEN - 1) / BYTES_PER_TOKEN
+    }
+}
+
+impl std::ops::Add for ContextCost {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            commands: self.commands + other.commands,
+            agents: self.agents + other.agents,
+            skills: self.skills + other.skills,
+            mcp_servers: self.mcp_servers + other.mcp_servers,
+        }
+    }
+}
+
+impl std::iter::Sum for ContextCost {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, cost| acc + cost)
+    }
+}
+
+/// Compact token count for the header: 850, 12.3k
+pub fn format_tokens(tokens: usize) -> String {
+    if tokens < 1000 {
+        tokens.to_string()
+    } else {
+        format!("{:.1}k", tokens as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::McpServerItem;
+    use std::path::PathBuf;
+
+    fn item(name: &str, description: Option<&str>) -> ContentItem {
+        ContentItem {
+            name: name.to_string(),
+            description: description.map(String::from),
+            path: PathBuf::from("x.md"),
+        }
+    }
+
+    #[test]
+    fn test_cost_of_contents() {
+        let contents = PluginContents {
+            commands: vec![item("test", Some("Run tests")), item("lint", None)],
+            agents: vec![item("reviewer", Some("Reviews"))],
+            mcp_servers: vec![McpServerItem {
+                name: "github".to_string(),
+                server: serde_json::from_value(serde_json::json!({ "url": "https://x" })).unwrap(),
+                path: PathBuf::from(".mcp.json"),
+            }],
+            ..Default::default()
+        };
+
+        let cost = ContextCost::of(&contents);
+        assert_eq!(cost.commands, 4 + 9 + 4);
+        assert_eq!(cost.agents, 8 + 7);
+        assert_eq!(cost.skills, 0);
+        assert_eq!(cost.mcp_servers, MCP_SERVER_BYTES);
+        assert_eq!(cost.bytes(), 17 + 15 + MCP_SERVER_BYTES);
+        assert_eq!(cost.tokens(), (17 + 15 + MCP_SERVER_BYTES + 3) / 4);
+    }
+
+    #[test]
+    fn test_sum_and_format() {
+        let a = ContextCost {
+            commands: 10,
+            ..Default::default()
+        };
+        let b = ContextCost {
+            skills: 5,
+            ..Default::default()
+        };
+        let total: ContextCost = [a, b].into_iter().sum();
+        assert_eq!(total.bytes(), 15);
+
+        assert_eq!(format_tokens(850), "850");
+        assert_eq!(format_tokens(12_345), "12.3k");
+    }
+}
@@ -1,5 +1,6 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use super::Scope;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// Claude Code settings.json structure
@@ -9,15 +10,85 @@ pub struct Settings {
     #[serde(default)]
     pub enabled_plugins: HashMap<String, bool>,
 
+    // Approvals for servers in the project's .mcp.json. These and the marketplaces
+    // below are read leniently: a malformed value must not make the whole file,
+    // and with it enabledPlugins, read as unset
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub enabled_mcpjson_servers: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub disabled_mcpjson_servers: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enable_all_project_mcp_servers: Option<bool>,
+
+    // Marketplaces a project asks its members to add, by name; bad entries are skipped
+    #[serde(
+        default,
+        deserialize_with = "lenient_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub extra_known_marketplaces: HashMap<String, ExtraMarketplace>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// Deserializes `T`, falling back to its default when the value has the wrong shape
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
+/// Deserializes a map entry by entry, dropping the entries that don't parse
+fn lenient_map<'de, D, T>(deserializer: D) -> Result<HashMap<String, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let serde_json::Value::Object(entries) = serde_json::Value::deserialize(deserializer)? else {
+        return Ok(HashMap::new());
+    };
+    Ok(entries
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, serde_json::from_value(value).ok()?)))
+        .collect())
+}
+
+/// An `extraKnownMarketplaces` entry in settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtraMarketplace {
+    pub source: MarketplaceSource,
+
     #[serde(flatten)]
     pub other: HashMap<String, serde_json::Value>,
 }
 
 /// Installed plugins tracking file structure
+///
+/// Every struct ccpm writes back keeps unknown keys in `other`, so fields added by
+/// newer Claude Code versions survive a read-modify-write round trip.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledPlugins {
     pub version: u32,
     pub plugins: HashMap<String, Vec<InstalledPluginEntry>>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
 }
 
 impl Default for InstalledPlugins {
@@ -25,6 +96,7 @@ impl Default for InstalledPlugins {
         Self {
             version: 2,
             plugins: HashMap::new(),
+            other: HashMap::new(),
         }
     }
 }
@@ -34,15 +106,101 @@ impl Default for InstalledPlugins {
 pub struct InstalledPluginEntry {
     pub scope: String,
     pub install_path: PathBuf,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub project_path: Option<PathBuf>, // For project/local scopes - the project directory
     pub version: String,
     pub installed_at: String,
     pub last_updated: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub git_commit_sha: Option<String>,
+    // Option so an absent key stays absent when written back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_local: Option<bool>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// ccpm's own registry of development plugins (~/.config/ccpm/dev_plugins.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevPlugins {
     #[serde(default)]
-    pub is_local: bool,
+    pub dev_plugins: Vec<DevPluginEntry>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevPluginEntry {
+    pub path: PathBuf,
+    pub added_at: String,
+    /// Read from plugin.json when the plugin was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// Install entries replaced by `ccpm update`, kept for rollback
+/// (~/.config/ccpm/previous_installs.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreviousInstalls {
+    #[serde(default)]
+    pub previous: BTreeMap<String, Vec<InstalledPluginEntry>>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// Plugins held at a version by `ccpm pin` (~/.config/ccpm/pins.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pins {
+    #[serde(default)]
+    pub pins: BTreeMap<String, Pin>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// The version and/or commit a plugin is held at; updates only move it there
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pin {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub pinned_at: String,
+}
+
+impl Pin {
+    /// Whether an install at `version` and `commit` satisfies the pin
+    pub fn allows(&self, version: &str, commit: Option<&str>) -> bool {
+        let version_ok = self.version.as_deref().map_or(true, |v| v == version);
+        let commit_ok = match (&self.commit, commit) {
+            (None, _) => true,
+            (Some(pinned), Some(commit)) => super::same_commit(pinned, commit),
+            (Some(_), None) => false,
+        };
+        version_ok && commit_ok
+    }
+}
+
+impl std::fmt::Display for Pin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.version, &self.commit) {
+            (Some(version), Some(commit)) => {
+                write!(f, "{} ({})", version, super::short_commit(commit))
+            }
+            (Some(version), None) => write!(f, "{}", version),
+            (None, Some(commit)) => write!(f, "{}", super::short_commit(commit)),
+            (None, None) => write!(f, "the installed version"),
+        }
+    }
 }
 
 /// Known marketplaces tracking file structure
@@ -58,18 +216,87 @@ pub struct MarketplaceEntry {
     pub source: MarketplaceSource,
     pub install_location: PathBuf,
     pub last_updated: String,
-    #[serde(default)]
-    pub auto_update: bool,
+    // Option so an absent key stays absent when written back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_update: Option<bool>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarketplaceSource {
-    pub source: String,
-    pub repo: String,
+impl MarketplaceEntry {
+    /// Auto-update flag, defaulting to off when the key is absent
+    pub fn auto_update_enabled(&self) -> bool {
+        self.auto_update.unwrap_or(false)
+    }
+}
+
+/// Where a marketplace comes from (the `source` object in known_marketplaces.json)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum MarketplaceSource {
+    /// GitHub repository in `owner/repo` form
+    Github {
+        repo: String,
+        #[serde(flatten)]
+        other: HashMap<String, serde_json::Value>,
+    },
+    /// Any git remote URL
+    Git {
+        url: String,
+        #[serde(flatten)]
+        other: HashMap<String, serde_json::Value>,
+    },
+    /// Local directory used in place
+    Directory {
+        path: PathBuf,
+        #[serde(flatten)]
+        other: HashMap<String, serde_json::Value>,
+    },
+    /// Source shape this version of ccpm does not know; kept verbatim
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+impl MarketplaceSource {
+    /// Source kind: "github", "git", "directory", or the raw `source` value
+    pub fn kind(&self) -> String {
+        match self {
+            MarketplaceSource::Github { .. } => "github".to_string(),
+            MarketplaceSource::Git { .. } => "git".to_string(),
+            MarketplaceSource::Directory { .. } => "directory".to_string(),
+            MarketplaceSource::Unknown(value) => value
+                .get("source")
+                .and_then(|s| s.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        }
+    }
+
+    /// Repository, URL or path the marketplace is fetched from
+    pub fn location(&self) -> String {
+        match self {
+            MarketplaceSource::Github { repo, .. } => repo.clone(),
+            MarketplaceSource::Git { url, .. } => url.clone(),
+            MarketplaceSource::Directory { path, .. } => path.display().to_string(),
+            MarketplaceSource::Unknown(value) => ["repo", "url", "path"]
+                .iter()
+                .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string()),
+        }
+    }
 }
 
-/// Plugin manifest file structure (plugin.json)
+impl std::fmt::Display for MarketplaceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.location(), self.kind())
+    }
+}
+
+/// Plugin manifest file structure (.claude-plugin/plugin.json)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PluginManifest {
     pub name: String,
     #[serde(default)]
@@ -79,21 +306,159 @@ pub struct PluginManifest {
     #[serde(default)]
     pub author: Option<PluginAuthor>,
     #[serde(default)]
-    pub mcp_servers: Option<HashMap<String, McpServer>>,
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub repository: Option<Repository>,
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    // Component locations: paths relative to the plugin root, or inline definitions
+    #[serde(default)]
+    pub commands: Option<ComponentRef<serde_json::Value>>,
+    #[serde(default)]
+    pub agents: Option<ComponentRef<serde_json::Value>>,
+    #[serde(default)]
+    pub hooks: Option<ComponentRef<serde_json::Value>>,
+    #[serde(default)]
+    pub mcp_servers: Option<ComponentRef<HashMap<String, McpServer>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginAuthor {
     pub name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
+/// Marketplace catalog (.claude-plugin/marketplace.json in the marketplace root)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplaceManifest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<PluginAuthor>,
+    #[serde(default)]
+    pub plugins: Vec<MarketplacePlugin>,
+
+    // BTreeMap so rewritten catalogs keep a stable key order
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// A plugin listed in a marketplace catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplacePlugin {
+    pub name: String,
+    /// A path relative to the marketplace root, or an object such as `{ "source": "github", "repo": ... }`
+    pub source: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl MarketplacePlugin {
+    /// The source path when the plugin lives inside the marketplace directory
+    pub fn local_source(&self) -> Option<&str> {
+        self.source.as_str()
+    }
+}
+
+/// `repository` is either a URL string or an npm-style `{ "type", "url" }` object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Repository {
+    Url(String),
+    Detailed {
+        #[serde(rename = "type", default)]
+        kind: Option<String>,
+        url: String,
+    },
+}
+
+impl Repository {
+    pub fn url(&self) -> &str {
+        match self {
+            Repository::Url(url) => url,
+            Repository::Detailed { url, .. } => url,
+        }
+    }
+}
+
+/// A manifest component field: one path, several paths, or an inline definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ComponentRef<T> {
+    Path(String),
+    Paths(Vec<String>),
+    Inline(T),
+}
+
+impl<T> ComponentRef<T> {
+    /// Referenced paths (empty for inline definitions)
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            ComponentRef::Path(path) => vec![path.as_str()],
+            ComponentRef::Paths(paths) => paths.iter().map(String::as_str).collect(),
+            ComponentRef::Inline(_) => Vec::new(),
+        }
+    }
+
+    pub fn inline(&self) -> Option<&T> {
+        match self {
+            ComponentRef::Inline(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// MCP server definition (stdio servers use `command`, http/sse servers use `url`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct McpServer {
-    pub command: String,
+    #[serde(rename = "type", default)]
+    pub transport: Option<String>,
+    #[serde(default)]
+    pub command: Option<String>,
     #[serde(default)]
     pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+impl McpServer {
+    /// "stdio", "http", "sse", ... (stdio when only a command is given)
+    pub fn transport_name(&self) -> &str {
+        match (&self.transport, &self.command) {
+            (Some(transport), _) => transport,
+            (None, Some(_)) => "stdio",
+            (None, None) => "http",
+        }
+    }
+
+    /// Command line for stdio servers, URL otherwise
+    pub fn target(&self) -> String {
+        match (&self.command, &self.url) {
+            (Some(command), _) => std::iter::once(command.as_str())
+                .chain(self.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+            (None, Some(url)) => url.clone(),
+            (None, None) => String::new(),
+        }
+    }
 }
 
 /// Paths for Claude Code configuration files
@@ -107,7 +472,12 @@ impl ConfigPaths {
     pub fn new() -> super::Result<Self> {
         let home = dirs::home_dir().ok_or(super::PluginError::HomeDirNotFound)?;
         let user_dir = home.join(".claude");
-        let local_dir = PathBuf::from(".claude");
+        // Absolute, so the paths ccpm saves in its global state (run markers,
+        // expiring enables, bisect) still name this project from another directory
+        let local_dir = std::env::current_dir()
+            .and_then(|cwd| cwd.canonicalize())
+            .unwrap_or_default()
+            .join(".claude");
 
         Ok(Self {
             user_dir,
@@ -129,6 +499,33 @@ impl ConfigPaths {
         self.local_dir.join("settings.local.json")
     }
 
+    /// Settings file for a scope. `project` points at another project's root directory;
+    /// None uses the CWD. User scope always resolves to `~/.claude/settings.json`.
+    pub fn settings_for(&self, scope: Scope, project: Option<&std::path::Path>) -> PathBuf {
+        let dir = match project {
+            Some(project) => project.join(".claude"),
+            None => self.local_dir.clone(),
+        };
+        match scope {
+            Scope::User => self.user_settings(),
+            Scope::Project => dir.join("settings.json"),
+            Scope::Local => dir.join("settings.local.json"),
+        }
+    }
+
+    /// User-level Claude config (~/.claude.json): user and per-project local MCP servers
+    pub fn user_config(&self) -> PathBuf {
+        self.user_dir.with_file_name(".claude.json")
+    }
+
+    /// Project-scope MCP servers (./.mcp.json), shared in git
+    pub fn project_mcp_config(&self) -> PathBuf {
+        self.local_dir
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new(""))
+            .join(".mcp.json")
+    }
+
     pub fn installed_plugins(&self) -> PathBuf {
         self.user_dir.join("plugins").join("installed_plugins.json")
     }
@@ -146,6 +543,40 @@ impl ConfigPaths {
     pub fn marketplaces(&self) -> PathBuf {
         self.user_dir.join("plugins").join("marketplaces")
     }
+
+    /// Directory for files owned by ccpm rather than Claude Code (~/.config/ccpm)
+    pub fn ccpm_dir(&self) -> PathBuf {
+        self.user_dir.with_file_name(".config").join("ccpm")
+    }
+
+    pub fn dev_plugins(&self) -> PathBuf {
+        self.ccpm_dir().join("dev_plugins.json")
+    }
+
+    /// Time-limited enables waiting to be reverted
+    pub fn expiring(&self) -> PathBuf {
+        self.ccpm_dir().join("expiring.json")
+    }
+
+    /// State of a running `ccpm bisect`
+    pub fn bisect(&self) -> PathBuf {
+        self.ccpm_dir().join("bisect.json")
+    }
+
+    /// Plugins held at a version by `ccpm pin`
+    pub fn pins(&self) -> PathBuf {
+        self.ccpm_dir().join("pins.json")
+    }
+
+    /// Install entries replaced by `ccpm update`
+    pub fn previous_installs(&self) -> PathBuf {
+        self.ccpm_dir().join("previous_installs.json")
+    }
+
+    /// Recovery markers for `ccpm run` sessions, one per ccpm process
+    pub fn run_markers(&self) -> PathBuf {
+        self.ccpm_dir().join("runs")
+    }
 }
 
 impl ConfigPaths {
@@ -236,6 +667,78 @@ mod tests {
             .contains("known_marketplaces.json"));
     }
 
+    #[test]
+    fn test_settings_extra_known_marketplaces() {
+        let json = r#"{
+            "enabledPlugins": {"lint@company-tools": true},
+            "extraKnownMarketplaces": {
+                "company-tools": {
+                    "source": {"source": "github", "repo": "company/claude-plugins", "ref": "stable"}
+                }
+            }
+        }"#;
+
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        let extra = &settings.extra_known_marketplaces["company-tools"];
+        assert_eq!(extra.source.kind(), "github");
+        assert_eq!(extra.source.location(), "company/claude-plugins");
+        assert!(!settings.other.contains_key("extraKnownMarketplaces"));
+
+        let written = serde_json::to_value(&settings).unwrap();
+        let original: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(written, original);
+    }
+
+    #[test]
+    fn test_settings_tolerates_malformed_fields() {
+        let json = r#"{
+            "enabledPlugins": {"lint@company-tools": true},
+            "enabledMcpjsonServers": "github",
+            "enableAllProjectMcpServers": "yes",
+            "extraKnownMarketplaces": {
+                "company-tools": {
+                    "source": {"source": "github", "repo": "company/claude-plugins"}
+                },
+                "broken": "company/other",
+                "no-source": {}
+            }
+        }"#;
+
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert!(settings.enabled_plugins["lint@company-tools"]);
+        assert!(settings.enabled_mcpjson_servers.is_empty());
+        assert_eq!(settings.enable_all_project_mcp_servers, None);
+        let names: Vec<_> = settings.extra_known_marketplaces.keys().collect();
+        assert_eq!(names, vec!["company-tools"]);
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"extraKnownMarketplaces": [], "enabledPlugins": {}}"#)
+                .unwrap();
+        assert!(settings.extra_known_marketplaces.is_empty());
+    }
+
+    #[test]
+    fn test_settings_for_scope_and_project() {
+        let paths = ConfigPaths {
+            user_dir: PathBuf::from("/home/me/.claude"),
+            local_dir: PathBuf::from(".claude"),
+        };
+        let other = std::path::Path::new("/work/other");
+
+        assert_eq!(
+            paths.settings_for(Scope::User, Some(other)),
+            PathBuf::from("/home/me/.claude/settings.json")
+        );
+        assert_eq!(
+            paths.settings_for(Scope::Project, None),
+            PathBuf::from(".claude/settings.json")
+        );
+        assert_eq!(
+            paths.settings_for(Scope::Local, Some(other)),
+            PathBuf::from("/work/other/.claude/settings.local.json")
+        );
+    }
+
     #[test]
     fn test_plugin_manifest_deserialize() {
         let json = r#"{
@@ -258,6 +761,67 @@ mod tests {
         assert_eq!(author.email, Some("test@example.com".to_string()));
     }
 
+    #[test]
+    fn test_plugin_manifest_full() {
+        let json = r#"{
+            "name": "full-plugin",
+            "version": "2.1.0",
+            "author": {"name": "Team", "url": "https://example.com"},
+            "homepage": "https://example.com/full-plugin",
+            "repository": {"type": "git", "url": "https://github.com/example/full-plugin"},
+            "license": "MIT",
+            "keywords": ["review", "testing"],
+            "commands": ["./commands", "./extra/deploy.md"],
+            "agents": "./agents",
+            "hooks": {"PreToolUse": [{"matcher": "Bash", "hooks": []}]},
+            "mcpServers": {
+                "db": {"command": "${CLAUDE_PLUGIN_ROOT}/bin/db", "args": ["--ro"], "env": {"DB_URL": "x"}},
+                "docs": {"type": "http", "url": "https://mcp.example.com"}
+            }
+        }"#;
+
+        let manifest: PluginManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            manifest.homepage.as_deref(),
+            Some("https://example.com/full-plugin")
+        );
+        assert_eq!(
+            manifest.repository.as_ref().map(Repository::url),
+            Some("https://github.com/example/full-plugin")
+        );
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+        assert_eq!(manifest.keywords, vec!["review", "testing"]);
+        assert_eq!(
+            manifest.commands.as_ref().unwrap().paths(),
+            vec!["./commands", "./extra/deploy.md"]
+        );
+        assert_eq!(manifest.agents.as_ref().unwrap().paths(), vec!["./agents"]);
+        assert!(manifest.hooks.as_ref().unwrap().inline().is_some());
+
+        let servers = manifest.mcp_servers.as_ref().unwrap().inline().unwrap();
+        assert_eq!(servers["db"].args, vec!["--ro"]);
+        assert_eq!(servers["docs"].transport.as_deref(), Some("http"));
+        assert_eq!(servers["docs"].command, None);
+    }
+
+    #[test]
+    fn test_plugin_manifest_component_paths() {
+        let json = r#"{
+            "name": "paths-plugin",
+            "repository": "https://github.com/example/paths-plugin",
+            "hooks": "./config/hooks.json",
+            "mcpServers": "./.mcp.json"
+        }"#;
+
+        let manifest: PluginManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            manifest.repository.unwrap().url(),
+            "https://github.com/example/paths-plugin"
+        );
+        assert_eq!(manifest.hooks.unwrap().paths(), vec!["./config/hooks.json"]);
+        assert_eq!(manifest.mcp_servers.unwrap().paths(), vec!["./.mcp.json"]);
+    }
+
     #[test]
     fn test_known_marketplaces_deserialize() {
         let json = r#"{
@@ -274,8 +838,93 @@ mod tests {
 
         let marketplaces: KnownMarketplaces = serde_json::from_str(json).unwrap();
         let entry = marketplaces.marketplaces.get("test-marketplace").unwrap();
-        assert_eq!(entry.source.source, "github");
-        assert_eq!(entry.source.repo, "owner/repo");
-        assert!(entry.auto_update);
+        assert_eq!(entry.source.kind(), "github");
+        assert_eq!(entry.source.location(), "owner/repo");
+        assert!(entry.auto_update_enabled());
+    }
+
+    #[test]
+    fn test_marketplace_source_variants() {
+        let marketplaces: KnownMarketplaces =
+            serde_json::from_str(include_str!("../../tests/fixtures/known_marketplaces.json"))
+                .unwrap();
+        let source = |name: &str| marketplaces.marketplaces[name].source.clone();
+
+        assert!(matches!(
+            source("claude-plugins-official"),
+            MarketplaceSource::Github { ref repo, .. } if repo == "anthropics/claude-plugins-official"
+        ));
+        assert!(matches!(
+            source("team-internal"),
+            MarketplaceSource::Git { ref url, .. } if url == "https://git.example.com/platform/claude-plugins.git"
+        ));
+        assert!(matches!(
+            source("local-dev"),
+            MarketplaceSource::Directory { ref path, .. } if path == &PathBuf::from("/Users/dev/work/plugins")
+        ));
+        assert!(matches!(source("hosted"), MarketplaceSource::Unknown(_)));
+
+        assert_eq!(source("hosted").kind(), "url");
+        assert_eq!(
+            source("hosted").location(),
+            "https://plugins.example.com/marketplace.json"
+        );
+        assert_eq!(
+            source("local-dev").to_string(),
+            "/Users/dev/work/plugins (directory)"
+        );
+    }
+
+    /// Serialize `T` parsed from `fixture` and compare the JSON values
+    fn assert_round_trip<T>(fixture: &str)
+    where
+        T: serde::de::DeserializeOwned + Serialize,
+    {
+        let parsed: T = serde_json::from_str(fixture).unwrap();
+        let written = serde_json::to_value(&parsed).unwrap();
+        let original: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        assert_eq!(written, original);
+    }
+
+    #[test]
+    fn test_installed_plugins_round_trip() {
+        assert_round_trip::<InstalledPlugins>(include_str!(
+            "../../tests/fixtures/installed_plugins.json"
+        ));
+    }
+
+    #[test]
+    fn test_known_marketplaces_round_trip() {
+        assert_round_trip::<KnownMarketplaces>(include_str!(
+            "../../tests/fixtures/known_marketplaces.json"
+        ));
+    }
+
+    #[test]
+    fn test_unknown_fields_are_kept() {
+        let installed: InstalledPlugins =
+            serde_json::from_str(include_str!("../../tests/fixtures/installed_plugins.json"))
+                .unwrap();
+        assert!(installed.other.contains_key("migratedFrom"));
+
+        let entry = &installed.plugins["agent-orchestration@claude-code-workflows"][0];
+        assert_eq!(
+            entry.other.get("installedBy"),
+            Some(&serde_json::json!("claude-code/2.1.4"))
+        );
+        assert_eq!(entry.is_local, None);
+
+        let marketplaces: KnownMarketplaces =
+            serde_json::from_str(include_str!("../../tests/fixtures/known_marketplaces.json"))
+                .unwrap();
+        let entry = &marketplaces.marketplaces["claude-code-workflows"];
+        assert!(entry.other.contains_key("lastSyncedCommit"));
+        match &entry.source {
+            MarketplaceSource::Github { other, .. } => {
+                assert_eq!(other.get("ref"), Some(&serde_json::json!("main")))
+            }
+            other => panic!("Expected github source, got {:?}", other),
+        }
+        assert_eq!(entry.auto_update, None);
     }
 }
@@ -1,6 +1,16 @@
 use super::{
-    config::{ConfigPaths, InstalledPlugins, KnownMarketplaces, PluginManifest, Settings},
-    Author, Plugin, Result, Scope,
+    changelog::{commits_since, diff_contents, Changelog},
+    config::{
+        ConfigPaths, DevPlugins, InstalledPlugins, KnownMarketplaces, MarketplaceEntry,
+        MarketplaceManifest, MarketplaceSource, Pins, PluginManifest, Settings,
+    },
+    contents::PluginContents,
+    marketplace::{MarketplaceSummary, PendingMarketplace},
+    scaffold::marketplace_manifest_path,
+    updates::{git_head, PluginUpdate},
+    validate::validate_plugin,
+    Author, McpApproval, McpApprovalSettings, McpOrigin, McpServer, McpServerEntry, Plugin,
+    PluginError, Result, Scope,
 };
 use std::collections::HashMap;
 use std::env;
@@ -34,7 +44,13 @@ impl PluginDiscovery {
         let cwd_local_settings = self.load_settings(&self.paths.local_settings());
 
         let installed = self.load_installed_plugins();
-        let _marketplaces = self.load_known_marketplaces();
+        let marketplaces = self.load_known_marketplaces();
+        let marketplace_source = |name: &str| {
+            marketplaces
+                .marketplaces
+                .get(name)
+                .map(|entry| entry.source.clone())
+        };
 
         // User enabled status (global)
         let mut user_enabled: HashMap<String, bool> = HashMap::new();
@@ -117,6 +133,7 @@ impl PluginDiscovery {
                 plugins.push(Plugin {
                     id: id.clone(),
                     name: manifest.as_ref().map(|m| m.name.clone()).unwrap_or(name),
+                    marketplace_source: marketplace_source(&marketplace),
                     marketplace,
                     description: manifest.as_ref().and_then(|m| m.description.clone()),
                     version: manifest
@@ -129,6 +146,16 @@ impl PluginDiscovery {
                             email: a.email.clone(),
                         })
                     }),
+                    homepage: manifest.as_ref().and_then(|m| m.homepage.clone()),
+                    repository: manifest
+                        .as_ref()
+                        .and_then(|m| m.repository.as_ref().map(|r| r.url().to_string())),
+                    license: manifest.as_ref().and_then(|m| m.license.clone()),
+                    keywords: manifest
+                        .as_ref()
+                        .map(|m| m.keywords.clone())
+                        .unwrap_or_default(),
+                    contents: PluginContents::scan(&entry.install_path, manifest.as_ref()),
                     install_scope,
                     install_path: Some(entry.install_path.clone()),
                     project_path: entry.project_path.clone(),
@@ -140,6 +167,8 @@ impl PluginDiscovery {
                     enabled_local: plugin_enabled_local,
                     installed_at: Some(entry.installed_at.clone()),
                     last_updated: Some(entry.last_updated.clone()),
+                    is_dev: false,
+                    diagnostics: Vec::new(),
                 });
             }
         }
@@ -158,10 +187,16 @@ impl PluginDiscovery {
                 plugins.push(Plugin {
                     id: id.clone(),
                     name,
+                    marketplace_source: marketplace_source(&marketplace),
                     marketplace,
                     description: None,
                     version: None,
                     author: None,
+                    homepage: None,
+                    repository: None,
+                    license: None,
+                    keywords: Vec::new(),
+                    contents: PluginContents::default(),
                     install_scope: Scope::User, // Not installed, default to user
                     install_path: None,
                     project_path: None,
@@ -172,16 +207,247 @@ impl PluginDiscovery {
                     enabled_local: cwd_local_enabled.get(id).copied(),
                     installed_at: None,
                     last_updated: None,
+                    is_dev: false,
+                    diagnostics: Vec::new(),
                 });
             }
         }
 
+        // Development plugins registered with `ccpm dev add`, validated on every load
+        for entry in self.load_dev_plugins().dev_plugins {
+            let manifest = self.load_plugin_manifest(&entry.path);
+            let mut diagnostics = validate_plugin(&entry.path);
+            for diagnostic in &mut diagnostics {
+                // Relative to the plugin root, which the details already show
+                if let Ok(relative) = diagnostic.path.strip_prefix(&entry.path) {
+                    diagnostic.path = relative.to_path_buf();
+                }
+            }
+            let name = manifest
+                .as_ref()
+                .map(|m| m.name.clone())
+                .or_else(|| entry.name.clone())
+                .unwrap_or_else(|| {
+                    entry
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                });
+            plugins.push(Plugin {
+                id: format!("{}@dev", name),
+                name,
+                marketplace: "dev".to_string(),
+                marketplace_source: None,
+                description: manifest.as_ref().and_then(|m| m.description.clone()),
+                version: manifest.as_ref().and_then(|m| m.version.clone()),
+                author: manifest.as_ref().and_then(|m| {
+                    m.author.as_ref().map(|a| Author {
+                        name: a.name.clone(),
+                        email: a.email.clone(),
+                    })
+                }),
+                homepage: manifest.as_ref().and_then(|m| m.homepage.clone()),
+                repository: manifest
+                    .as_ref()
+                    .and_then(|m| m.repository.as_ref().map(|r| r.url().to_string())),
+                license: manifest.as_ref().and_then(|m| m.license.clone()),
+                keywords: manifest
+                    .as_ref()
+                    .map(|m| m.keywords.clone())
+                    .unwrap_or_default(),
+                contents: PluginContents::scan(&entry.path, manifest.as_ref()),
+                install_scope: Scope::User,
+                install_path: Some(entry.path.clone()),
+                project_path: None,
+                is_current_project: true,
+                enabled_user: None,
+                enabled_project: None,
+                enabled_local: None,
+                installed_at: Some(entry.added_at.clone()),
+                last_updated: None,
+                is_dev: true,
+                diagnostics,
+            });
+        }
+
         // Sort by name
-        plugins.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        plugins.sort_by_key(|a| a.name.to_lowercase());
 
         Ok(plugins)
     }
 
+    /// Update status of every installed plugin against its marketplace checkout.
+    /// Plugins inside the marketplace repository compare against its HEAD; plugins
+    /// with their own source against the `sha` pinned in the catalog, if any.
+    pub fn check_updates(&self) -> Vec<PluginUpdate> {
+        let installed = self.load_installed_plugins();
+        let marketplaces = self.load_known_marketplaces();
+        let mut catalogs: HashMap<String, Option<MarketplaceManifest>> = HashMap::new();
+        let mut pins = self.load_pins();
+
+        let mut updates = Vec::new();
+        for (id, entries) in &installed.plugins {
+            let Some(entry) = entries.first() else {
+                continue;
+            };
+            let (name, marketplace) = parse_plugin_id(id);
+            let checkout = marketplaces
+                .marketplaces
+                .get(&marketplace)
+                .map(|m| m.install_location.clone());
+            let catalog = catalogs
+                .entry(marketplace)
+                .or_insert_with(|| checkout.as_deref().and_then(load_catalog));
+            let listed = catalog
+                .as_ref()
+                .and_then(|c| c.plugins.iter().find(|p| p.name == name));
+
+            let (version, commit) = match (listed, &checkout) {
+                (Some(listed), Some(dir)) => match listed.local_source() {
+                    Some(source) => (
+                        listed.version.clone().or_else(|| {
+                            self.load_plugin_manifest(&dir.join(source))
+                                .and_then(|m| m.version)
+                        }),
+                        git_head(dir),
+                    ),
+                    None => (
+                        listed.version.clone(),
+                        listed
+                            .source
+                            .get("sha")
+                            .and_then(|sha| sha.as_str())
+                            .map(str::to_string),
+                    ),
+                },
+                _ => (None, None),
+            };
+            let mut update = PluginUpdate::new(
+                id.clone(),
+                entry.version.clone(),
+                entry.git_commit_sha.clone(),
+                version,
+                commit,
+            );
+            update.pin = pins.pins.remove(id);
+            update.source_dir = listed
+                .and_then(|l| l.local_source())
+                .zip(checkout.as_ref())
+                .map(|(source, dir)| dir.join(source));
+            updates.push(update);
+        }
+        updates.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+        updates
+    }
+
+    /// What updating a plugin would bring: commits touching its directory in the
+    /// marketplace checkout since the installed commit, and the components that
+    /// differ between the installed copy and the checkout
+    pub fn changelog(&self, id: &str) -> Result<Changelog> {
+        let update = self
+            .check_updates()
+            .into_iter()
+            .find(|u| u.plugin_id == id)
+            .ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+        let source_dir = update
+            .source_dir
+            .clone()
+            .ok_or_else(|| PluginError::NotUpdatable(id.to_string()))?;
+        let installed_commit = update
+            .installed_commit
+            .clone()
+            .ok_or_else(|| PluginError::NoInstalledCommit(id.to_string()))?;
+        let install_path = self
+            .load_installed_plugins()
+            .plugins
+            .get(id)
+            .and_then(|entries| entries.first())
+            .map(|entry| entry.install_path.clone())
+            .ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+
+        let commits = commits_since(&source_dir, &installed_commit)?;
+        let installed = PluginContents::scan(
+            &install_path,
+            self.load_plugin_manifest(&install_path).as_ref(),
+        );
+        let available =
+            PluginContents::scan(&source_dir, self.load_plugin_manifest(&source_dir).as_ref());
+
+        Ok(Changelog {
+            plugin_id: update.plugin_id,
+            installed_version: update.installed_version,
+            installed_commit,
+            available_version: update.available_version,
+            available_commit: update.available_commit,
+            commits,
+            changes: diff_contents(&installed, &available),
+        })
+    }
+
+    /// Every MCP server Claude Code would see in the current project: local and user
+    /// servers from `~/.claude.json`, the project `.mcp.json`, then active plugins
+    pub fn discover_mcp_servers(&self, plugins: &[Plugin]) -> Vec<McpServerEntry> {
+        let mut entries = Vec::new();
+
+        let user_config_path = self.paths.user_config();
+        let user_config = read_json(&user_config_path);
+        let cwd = env::current_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if let Some(ref config) = user_config {
+            let local = config
+                .get("projects")
+                .and_then(|p| p.get(&cwd))
+                .and_then(|p| p.get("mcpServers"));
+            entries.extend(server_entries(
+                local,
+                McpOrigin::Local(user_config_path.clone()),
+                |_| McpApproval::NotRequired,
+            ));
+        }
+
+        let project_path = self.paths.project_mcp_config();
+        let approvals = self.mcp_approval_settings();
+        let project = read_json(&project_path);
+        entries.extend(server_entries(
+            project.as_ref().and_then(|p| p.get("mcpServers")),
+            McpOrigin::Project(project_path.clone()),
+            |name| approvals.approval(name),
+        ));
+
+        if let Some(ref config) = user_config {
+            entries.extend(server_entries(
+                config.get("mcpServers"),
+                McpOrigin::User(user_config_path.clone()),
+                |_| McpApproval::NotRequired,
+            ));
+        }
+
+        for plugin in plugins.iter().filter(|p| p.is_active()) {
+            for item in &plugin.contents.mcp_servers {
+                entries.push(McpServerEntry {
+                    name: item.name.clone(),
+                    origin: McpOrigin::Plugin(plugin.id.clone()),
+                    server: item.server.clone(),
+                    approval: McpApproval::NotRequired,
+                });
+            }
+        }
+
+        entries
+    }
+
+    /// Settings in the current project that hold `.mcp.json` approvals
+    pub fn mcp_approval_settings(&self) -> McpApprovalSettings {
+        let load = |path: PathBuf| path.exists().then(|| self.load_settings(&path));
+        McpApprovalSettings {
+            local: load(self.paths.local_settings()),
+            project: load(self.paths.project_settings()),
+            user: load(self.paths.user_settings()),
+        }
+    }
+
     /// Check if a local install path is within the current working directory
     fn is_local_install_current_project(&self, install_path: &Path) -> bool {
         if let Ok(cwd) = env::current_dir() {
@@ -193,16 +459,75 @@ impl PluginDiscovery {
         }
     }
 
-    /// Get marketplace info
-    pub fn get_marketplaces(&self) -> HashMap<String, bool> {
-        let marketplaces = self.load_known_marketplaces();
-        marketplaces
+    /// Registered marketplaces, sorted by name
+    pub fn marketplaces(&self) -> Vec<(String, MarketplaceEntry)> {
+        let mut marketplaces: Vec<_> = self
+            .load_known_marketplaces()
             .marketplaces
-            .iter()
-            .map(|(name, entry)| (name.clone(), entry.auto_update))
+            .into_iter()
+            .collect();
+        marketplaces.sort_by(|a, b| a.0.cmp(&b.0));
+        marketplaces
+    }
+
+    /// Registered marketplaces with their catalogs and installed plugins, sorted by name
+    pub fn marketplace_summaries(&self) -> Vec<MarketplaceSummary> {
+        let installed = self.load_installed_plugins();
+        self.marketplaces()
+            .into_iter()
+            .map(|(name, entry)| {
+                let mut plugins: Vec<String> = installed
+                    .plugins
+                    .keys()
+                    .filter_map(|id| id.split_once('@'))
+                    .filter(|(_, marketplace)| *marketplace == name)
+                    .map(|(plugin, _)| plugin.to_string())
+                    .collect();
+                plugins.sort();
+                MarketplaceSummary {
+                    head: git_head(&entry.install_location),
+                    catalog: load_catalog(&entry.install_location),
+                    installed: plugins,
+                    name,
+                    entry,
+                }
+            })
             .collect()
     }
 
+    /// Marketplaces this project declares under `extraKnownMarketplaces` (project,
+    /// then local settings) that are not registered yet, sorted by name
+    pub fn pending_marketplaces(&self) -> Vec<PendingMarketplace> {
+        let known = self.load_known_marketplaces();
+        let project_root = self.paths.local_dir.parent().unwrap_or(Path::new(""));
+        let mut pending: Vec<PendingMarketplace> = Vec::new();
+        for path in [self.paths.project_settings(), self.paths.local_settings()] {
+            for (name, extra) in self.load_settings(&path).extra_known_marketplaces {
+                if known.marketplaces.contains_key(&name) || pending.iter().any(|p| p.name == name)
+                {
+                    continue;
+                }
+                // Directory sources are relative to the project root
+                let source = match extra.source {
+                    MarketplaceSource::Directory { path, other } if path.is_relative() => {
+                        MarketplaceSource::Directory {
+                            path: project_root.join(path),
+                            other,
+                        }
+                    }
+                    source => source,
+                };
+                pending.push(PendingMarketplace {
+                    name,
+                    source,
+                    declared_in: path.clone(),
+                });
+            }
+        }
+        pending.sort_by(|a, b| a.name.cmp(&b.name));
+        pending
+    }
+
     fn load_settings(&self, path: &Path) -> Settings {
         if !path.exists() {
             return Settings::default();
@@ -238,7 +563,28 @@ impl PluginDiscovery {
             .unwrap_or_default()
     }
 
-    fn load_plugin_manifest(&self, install_path: &Path) -> Option<PluginManifest> {
+    /// Plugins held at a version (~/.config/ccpm/pins.json)
+    pub(crate) fn load_pins(&self) -> Pins {
+        fs::read_to_string(self.paths.pins())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Registry of development plugins (~/.config/ccpm/dev_plugins.json)
+    pub(crate) fn load_dev_plugins(&self) -> DevPlugins {
+        let path = self.paths.dev_plugins();
+        if !path.exists() {
+            return DevPlugins::default();
+        }
+
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn load_plugin_manifest(&self, install_path: &Path) -> Option<PluginManifest> {
         let manifest_path = install_path.join(".claude-plugin").join("plugin.json");
         if !manifest_path.exists() {
             return None;
@@ -250,6 +596,44 @@ impl PluginDiscovery {
     }
 }
 
+/// The catalog of a marketplace checkout (.claude-plugin/marketplace.json)
+pub(crate) fn load_catalog(dir: &Path) -> Option<MarketplaceManifest> {
+    fs::read_to_string(marketplace_manifest_path(dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Entries for a `mcpServers` map, sorted by name; malformed servers are skipped
+fn server_entries(
+    servers: Option<&serde_json::Value>,
+    origin: McpOrigin,
+    approval: impl Fn(&str) -> McpApproval,
+) -> Vec<McpServerEntry> {
+    let Some(servers) = servers.and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<McpServerEntry> = servers
+        .iter()
+        .filter_map(|(name, value)| {
+            let server = serde_json::from_value::<McpServer>(value.clone()).ok()?;
+            Some(McpServerEntry {
+                name: name.clone(),
+                origin: origin.clone(),
+                server,
+                approval: approval(name),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
 /// Parse plugin ID into (name, marketplace)
 fn parse_plugin_id(id: &str) -> (String, String) {
     if let Some(pos) = id.rfind('@') {
@@ -262,6 +646,7 @@ fn parse_plugin_id(id: &str) -> (String, String) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugin::{ScopeFilter, UpdateStatus};
     use tempfile::TempDir;
 
     #[test]
@@ -359,9 +744,15 @@ mod tests {
             id: "test@marketplace".to_string(),
             name: "test".to_string(),
             marketplace: "marketplace".to_string(),
+            marketplace_source: None,
             description: None,
             version: None,
             author: None,
+            homepage: None,
+            repository: None,
+            license: None,
+            keywords: Vec::new(),
+            contents: PluginContents::default(),
             install_scope: Scope::Local,
             install_path: None,
             project_path: Some(project_a.path().to_path_buf()),
@@ -371,6 +762,8 @@ mod tests {
             enabled_local: local_enabled,
             installed_at: None,
             last_updated: None,
+            is_dev: false,
+            diagnostics: Vec::new(),
         };
 
         // Local false MUST override Project true
@@ -387,9 +780,15 @@ mod tests {
             id: "test@marketplace".to_string(),
             name: "test".to_string(),
             marketplace: "marketplace".to_string(),
+            marketplace_source: None,
             description: None,
             version: None,
             author: None,
+            homepage: None,
+            repository: None,
+            license: None,
+            keywords: Vec::new(),
+            contents: PluginContents::default(),
             install_scope: Scope::Local,
             install_path: None,
             project_path: None,
@@ -399,6 +798,8 @@ mod tests {
             enabled_local: Some(false),   // Local says disabled
             installed_at: None,
             last_updated: None,
+            is_dev: false,
+            diagnostics: Vec::new(),
         };
 
         // Local false MUST override Project true
@@ -445,9 +846,15 @@ mod tests {
             id: "test@marketplace".to_string(),
             name: "test".to_string(),
             marketplace: "marketplace".to_string(),
+            marketplace_source: None,
             description: None,
             version: None,
             author: None,
+            homepage: None,
+            repository: None,
+            license: None,
+            keywords: Vec::new(),
+            contents: PluginContents::default(),
             install_scope: Scope::User, // User scope!
             install_path: None,
             project_path: None,
@@ -457,6 +864,8 @@ mod tests {
             enabled_local: None,       // Should be None for user-scope plugins
             installed_at: None,
             last_updated: None,
+            is_dev: false,
+            diagnostics: Vec::new(),
         };
 
         assert!(
@@ -470,6 +879,207 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_discover_attaches_marketplace_source() {
+        let temp = TempDir::new().unwrap();
+        let paths = ConfigPaths {
+            user_dir: temp.path().join("user"),
+            local_dir: temp.path().join("local"),
+        };
+        fs::create_dir_all(paths.installed_plugins().parent().unwrap()).unwrap();
+        fs::write(
+            paths.known_marketplaces(),
+            include_str!("../../tests/fixtures/known_marketplaces.json"),
+        )
+        .unwrap();
+        fs::write(
+            paths.installed_plugins(),
+            serde_json::json!({
+                "version": 2,
+                "plugins": {
+                    "tool@local-dev": [{
+                        "scope": "user",
+                        "installPath": temp.path().join("tool"),
+                        "version": "0.1.0",
+                        "installedAt": "2026-01-01T00:00:00Z",
+                        "lastUpdated": "2026-01-01T00:00:00Z"
+                    }]
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let plugins = PluginDiscovery::with_paths(paths).discover_all().unwrap();
+        let source = plugins[0].marketplace_source.as_ref().unwrap();
+        assert_eq!(source.kind(), "directory");
+        assert_eq!(source.location(), "/Users/dev/work/plugins");
+    }
+
+    /// A directory marketplace "market" checked out at b*40 with three plugins,
+    /// plus installs of all three and one from an unknown marketplace
+    fn market_fixture(temp: &TempDir) -> PluginDiscovery {
+        let paths = ConfigPaths {
+            user_dir: temp.path().join("user"),
+            local_dir: temp.path().join("local"),
+        };
+        let checkout = temp.path().join("market");
+        fs::create_dir_all(checkout.join(".git/refs/heads")).unwrap();
+        fs::write(checkout.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(checkout.join(".git/refs/heads/main"), "b".repeat(40)).unwrap();
+        fs::create_dir_all(checkout.join(".claude-plugin")).unwrap();
+        fs::write(
+            marketplace_manifest_path(&checkout),
+            serde_json::json!({
+                "name": "market",
+                "plugins": [
+                    {"name": "bumped", "source": "./bumped", "version": "1.1.0"},
+                    {"name": "moved", "source": "./moved", "version": "1.0.0"},
+                    {"name": "current", "source": "./current", "version": "1.0.0"}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        fs::create_dir_all(paths.installed_plugins().parent().unwrap()).unwrap();
+        fs::write(
+            paths.known_marketplaces(),
+            serde_json::json!({
+                "market": {
+                    "source": {"source": "directory", "path": checkout},
+                    "installLocation": checkout,
+                    "lastUpdated": "2026-01-01T00:00:00Z"
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let entry = |version: &str, sha: String| {
+            serde_json::json!([{
+                "scope": "user",
+                "installPath": temp.path().join("cache"),
+                "version": version,
+                "installedAt": "2026-01-01T00:00:00Z",
+                "lastUpdated": "2026-01-01T00:00:00Z",
+                "gitCommitSha": sha
+            }])
+        };
+        fs::write(
+            paths.installed_plugins(),
+            serde_json::json!({
+                "version": 2,
+                "plugins": {
+                    "bumped@market": entry("1.0.0", "b".repeat(40)),
+                    "moved@market": entry("1.0.0", "a".repeat(40)),
+                    "current@market": entry("1.0.0", "b".repeat(40)),
+                    "gone@elsewhere": entry("1.0.0", "b".repeat(40))
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        PluginDiscovery::with_paths(paths)
+    }
+
+    #[test]
+    fn test_check_updates() {
+        let temp = TempDir::new().unwrap();
+        let discovery = market_fixture(&temp);
+        let updates = discovery.check_updates();
+        let statuses: Vec<_> = updates
+            .iter()
+            .map(|u| (u.plugin_id.as_str(), u.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("bumped@market", UpdateStatus::NewerVersion),
+                ("current@market", UpdateStatus::UpToDate),
+                ("gone@elsewhere", UpdateStatus::Unknown),
+                ("moved@market", UpdateStatus::DifferentCommit),
+            ]
+        );
+        assert_eq!(updates[0].available_version.as_deref(), Some("1.1.0"));
+    }
+
+    #[test]
+    fn test_marketplace_summaries() {
+        let temp = TempDir::new().unwrap();
+        let discovery = market_fixture(&temp);
+        let summaries = discovery.marketplace_summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].name, "market");
+        assert_eq!(summaries[0].head, Some("b".repeat(40)));
+        assert_eq!(summaries[0].available_count(), 3);
+        assert_eq!(summaries[0].installed, vec!["bumped", "current", "moved"]);
+        assert!(summaries[0].is_installed("moved"));
+        assert!(!summaries[0].is_installed("gone"));
+    }
+
+    #[test]
+    fn test_pending_marketplaces() {
+        let temp = TempDir::new().unwrap();
+        let paths = ConfigPaths {
+            user_dir: temp.path().join("home").join(".claude"),
+            local_dir: temp.path().join("project").join(".claude"),
+        };
+        fs::create_dir_all(paths.known_marketplaces().parent().unwrap()).unwrap();
+        fs::create_dir_all(&paths.local_dir).unwrap();
+        fs::write(
+            paths.known_marketplaces(),
+            serde_json::json!({
+                "added": {
+                    "source": {"source": "github", "repo": "team/added"},
+                    "installLocation": temp.path().join("added"),
+                    "lastUpdated": "2026-01-01T00:00:00Z"
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            paths.project_settings(),
+            serde_json::json!({
+                "extraKnownMarketplaces": {
+                    "added": {"source": {"source": "github", "repo": "team/added"}},
+                    "company-tools": {"source": {"source": "github", "repo": "company/tools"}},
+                    "vendored": {"source": {"source": "directory", "path": "./plugins"}}
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            paths.local_settings(),
+            serde_json::json!({
+                "extraKnownMarketplaces": {
+                    "company-tools": {"source": {"source": "git", "url": "https://example.com/fork.git"}},
+                    "mine": {"source": {"source": "git", "url": "https://example.com/mine.git"}}
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let pending = PluginDiscovery::with_paths(paths.clone()).pending_marketplaces();
+        let names: Vec<_> = pending.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["company-tools", "mine", "vendored"]);
+        // Project settings win over local ones
+        assert_eq!(pending[0].source.kind(), "github");
+        assert_eq!(pending[0].declared_in, paths.project_settings());
+        assert_eq!(pending[1].declared_in, paths.local_settings());
+        assert_eq!(
+            pending[2].source.location(),
+            temp.path()
+                .join("project")
+                .join("./plugins")
+                .display()
+                .to_string()
+        );
+    }
+
     #[test]
     fn test_load_settings_from_nonexistent_project() {
         // Should return (None, None) for non-existent path
@@ -481,4 +1091,86 @@ mod tests {
         assert!(proj_settings.is_none(), "Should be None for non-existent project");
         assert!(local_settings.is_none(), "Should be None for non-existent local");
     }
+
+    #[test]
+    fn test_discover_mcp_servers() {
+        let temp = TempDir::new().unwrap();
+        let paths = ConfigPaths {
+            user_dir: temp.path().join("home").join(".claude"),
+            local_dir: temp.path().join("project").join(".claude"),
+        };
+        fs::create_dir_all(&paths.user_dir).unwrap();
+        fs::create_dir_all(&paths.local_dir).unwrap();
+        fs::write(
+            paths.project_mcp_config(),
+            serde_json::json!({
+                "mcpServers": {
+                    "github": { "command": "gh-mcp", "env": { "GITHUB_TOKEN": "secret" } },
+                    "docs": { "type": "http", "url": "https://docs.example/mcp" }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            paths.local_settings(),
+            r#"{ "enabledMcpjsonServers": ["docs"] }"#,
+        )
+        .unwrap();
+        fs::write(
+            paths.user_config(),
+            r#"{ "mcpServers": { "notes": { "command": "notes-mcp" } } }"#,
+        )
+        .unwrap();
+
+        let servers = PluginDiscovery::with_paths(paths).discover_mcp_servers(&[]);
+        let summary: Vec<_> = servers
+            .iter()
+            .map(|s| (s.name.as_str(), s.origin.label(), s.approval))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("docs", "project", McpApproval::Approved(Scope::Local)),
+                ("github", "project", McpApproval::Pending),
+                ("notes", "user", McpApproval::NotRequired),
+            ]
+        );
+        assert_eq!(servers[1].redacted_env(), vec!["GITHUB_TOKEN=***"]);
+    }
+
+    #[test]
+    fn test_discover_dev_plugins_with_diagnostics() {
+        let temp = TempDir::new().unwrap();
+        let paths = ConfigPaths {
+            user_dir: temp.path().join("home").join(".claude"),
+            local_dir: temp.path().join("project").join(".claude"),
+        };
+        let dir = temp.path().join("wip");
+        fs::create_dir_all(dir.join(".claude-plugin")).unwrap();
+        fs::write(
+            dir.join(".claude-plugin").join("plugin.json"),
+            r#"{"name": "wip", "version": "not-semver"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(paths.ccpm_dir()).unwrap();
+        fs::write(
+            paths.dev_plugins(),
+            serde_json::json!({
+                "devPlugins": [{ "path": dir, "addedAt": "2026-01-01T00:00:00Z" }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let plugins = PluginDiscovery::with_paths(paths).discover_all().unwrap();
+        assert_eq!(plugins.len(), 1);
+        let plugin = &plugins[0];
+        assert_eq!(plugin.id, "wip@dev");
+        assert!(plugin.is_dev);
+        assert_eq!(plugin.scope_indicator(), "[D]");
+        assert!(!plugin.diagnostics.is_empty());
+        assert!(ScopeFilter::Dev.matches(plugin));
+        assert!(!ScopeFilter::User.matches(plugin));
+    }
 }
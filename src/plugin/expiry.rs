@@ -0,0 +1,118 @@
+//! Time-limited enables (`ccpm enable --for 2h`).
+//!
+//! Each one is recorded in `~/.config/ccpm/expiring.json` together with the value
+//! the settings file held before, so it can be put back once the time is up.
+//! Expired entries are reverted at the next ccpm start, by `ccpm gc`, or by the
+//! TUI while it is open.
+
+use super::Scope;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// ccpm's record of time-limited enables
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpiringEnables {
+    #[serde(default)]
+    pub expiring: Vec<ExpiringEnable>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiringEnable {
+    pub plugin_id: String,
+    pub scope: Scope,
+    /// Settings file the enable was written to
+    pub settings_path: PathBuf,
+    /// Explicit value before the enable; None if the plugin was not listed
+    #[serde(default)]
+    pub previous: Option<bool>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ExpiringEnable {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+
+    /// Countdown for the plugin list, e.g. `1h20m`
+    pub fn remaining(&self, now: DateTime<Utc>) -> String {
+        format_duration(self.expires_at - now)
+    }
+}
+
+/// Longest accepted duration (a year); keeps expiry timestamps well within range
+const MAX_DURATION_SECONDS: i64 = 365 * 86_400;
+
+/// Parse durations like `90s`, `45m`, `2h`, `1d` or `1h30m`
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let mut seconds: i64 = 0;
+    let mut digits = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: i64 = digits.parse().ok()?;
+        digits.clear();
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return None,
+        };
+        seconds = seconds.checked_add(amount.checked_mul(unit)?)?;
+    }
+    // A trailing number without a unit is ambiguous
+    if !digits.is_empty() || seconds <= 0 || seconds > MAX_DURATION_SECONDS {
+        return None;
+    }
+    Some(Duration::seconds(seconds))
+}
+
+/// Compact duration: the two largest units, e.g. `2d3h`, `1h20m`, `45m`, `30s`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h{}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d{}h", d, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Some(Duration::hours(2)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1d"), Some(Duration::days(1)));
+        assert_eq!(parse_duration("45s"), Some(Duration::seconds(45)));
+        assert_eq!(parse_duration("2"), None);
+        assert_eq!(parse_duration("2x"), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("366d"), None);
+        assert_eq!(parse_duration("99999999999999999d"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(80)), "1h20m");
+        assert_eq!(format_duration(Duration::hours(2)), "2h");
+        assert_eq!(format_duration(Duration::seconds(59)), "59s");
+        assert_eq!(format_duration(Duration::hours(51)), "2d3h");
+        assert_eq!(format_duration(Duration::seconds(-5)), "0s");
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+/// YAML frontmatter of a command, agent or skill markdown file.
+///
+/// Only the flat `key: value` subset used by Claude Code components is understood:
+/// plain and quoted scalars, plus `|`/`>` blocks and indented continuation lines,
+/// which are folded into a single line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub fields: HashMap<String, String>,
+    /// 1-based line number of each key, for diagnostics
+    pub lines: HashMap<String, usize>,
+}
+
+impl Frontmatter {
+    /// Parse the block between the leading `---` fences. Returns None when the
+    /// document has no frontmatter or the closing fence is missing.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines().enumerate();
+        let (_, first) = lines.next()?;
+        if first.trim_start_matches('\u{feff}').trim_end() != "---" {
+            return None;
+        }
+
+        let mut frontmatter = Frontmatter::default();
+        let mut current: Option<String> = None;
+
+        for (index, line) in lines {
+            if line.trim_end() == "---" {
+                return Some(frontmatter);
+            }
+
+            let is_continuation = line.starts_with(' ') || line.starts_with('\t');
+            if is_continuation || line.trim().is_empty() {
+                if let Some(ref key) = current {
+                    let part = line.trim();
+                    if !part.is_empty() {
+                        let value = frontmatter.fields.entry(key.clone()).or_default();
+                        if !value.is_empty() {
+                            value.push(' ');
+                        }
+                        value.push_str(part);
+                    }
+                }
+                continue;
+            }
+
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(':') {
+                Some((key, value)) => {
+                    let key = key.trim().to_string();
+                    let value = match value.trim() {
+                        "|" | ">" | "|-" | ">-" => String::new(),
+                        value => unquote(value).to_string(),
+                    };
+                    frontmatter.lines.insert(key.clone(), index + 1);
+                    frontmatter.fields.insert(key.clone(), value);
+                    current = Some(key);
+                }
+                None => current = None,
+            }
+        }
+
+        None
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .get(key)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_fields() {
+        let text = "---\nname: reviewer\ndescription: \"Reviews code: carefully\"\ntools: Read, Grep\n---\n\nBody";
+        let fm = Frontmatter::parse(text).unwrap();
+        assert_eq!(fm.get("name"), Some("reviewer"));
+        assert_eq!(fm.get("description"), Some("Reviews code: carefully"));
+        assert_eq!(fm.get("tools"), Some("Read, Grep"));
+        assert_eq!(fm.lines["description"], 3);
+    }
+
+    #[test]
+    fn test_parse_block_scalar() {
+        let text = "---\ndescription: >\n  Runs the tests\n  and reports\nmodel: sonnet\n---\n";
+        let fm = Frontmatter::parse(text).unwrap();
+        assert_eq!(fm.get("description"), Some("Runs the tests and reports"));
+        assert_eq!(fm.get("model"), Some("sonnet"));
+    }
+
+    #[test]
+    fn test_missing_frontmatter() {
+        assert!(Frontmatter::parse("# Just markdown").is_none());
+        assert!(Frontmatter::parse("---\nname: unterminated\n").is_none());
+    }
+
+    #[test]
+    fn test_empty_value_is_none() {
+        let fm = Frontmatter::parse("---\ndescription:\n---\n").unwrap();
+        assert_eq!(fm.get("description"), None);
+        assert!(fm.fields.contains_key("description"));
+    }
+}
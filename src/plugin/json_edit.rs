@@ -0,0 +1,494 @@
+//! Format-preserving edits of JSON documents.
+//!
+//! Settings files are committed to git, so rewriting them through serde would reorder
+//! keys and reformat the whole file. These helpers splice a single member into the
+//! original text instead, keeping key order, indentation, line endings and the
+//! trailing newline untouched.
+
+use serde_json::Value;
+
+/// Set `document[object_key][member_key] = value`, editing the source text in place.
+///
+/// Creates `object_key` as a nested object when it is missing.
+/// Returns None when `source` is not a well-formed JSON object.
+pub fn set_nested_member(
+    source: &str,
+    object_key: &str,
+    member_key: &str,
+    value: &Value,
+) -> Option<String> {
+    serde_json::from_str::<serde_json::Map<String, Value>>(source).ok()?;
+
+    let value_text = serde_json::to_string(value).ok()?;
+    let root = Scanner::new(source).root_object()?;
+    let style = Style::detect(source, &root);
+
+    match root.member(object_key) {
+        Some(member) if source[member.value_start..].starts_with('{') => {
+            let object = Scanner::at(source, member.value_start).object()?;
+            Some(set_member(source, &object, member_key, &value_text, &style))
+        }
+        Some(member) => {
+            // Present but not an object: replace it wholesale
+            let indent = line_indent(source, member.key_start);
+            let nested = style.nested_object(&indent, member_key, &value_text);
+            Some(splice(
+                source,
+                member.value_start,
+                member.value_end,
+                &nested,
+            ))
+        }
+        None => {
+            let indent = root
+                .member_indent(source)
+                .unwrap_or_else(|| style.unit.clone());
+            let nested = style.nested_object(&indent, member_key, &value_text);
+            Some(set_member(source, &root, object_key, &nested, &style))
+        }
+    }
+}
+
+/// Set `document[key] = value` on the root object, editing the source text in place.
+///
+/// Returns None when `source` is not a well-formed JSON object.
+pub fn set_top_level_member(source: &str, key: &str, value: &Value) -> Option<String> {
+    serde_json::from_str::<serde_json::Map<String, Value>>(source).ok()?;
+
+    let value_text = serde_json::to_string(value).ok()?;
+    let root = Scanner::new(source).root_object()?;
+    let style = Style::detect(source, &root);
+    Some(set_member(source, &root, key, &value_text, &style))
+}
+
+/// Remove `document[object_key][member_key]`, editing the source text in place.
+///
+/// The document is returned unchanged when the member is absent.
+/// Returns None when `source` is not a well-formed JSON object.
+pub fn remove_nested_member(source: &str, object_key: &str, member_key: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Map<String, Value>>(source).ok()?;
+
+    let root = Scanner::new(source).root_object()?;
+    let Some(parent) = root.member(object_key) else {
+        return Some(source.to_string());
+    };
+    if !source[parent.value_start..].starts_with('{') {
+        return Some(source.to_string());
+    }
+    let object = Scanner::at(source, parent.value_start).object()?;
+    let Some(index) = object.members.iter().rposition(|m| m.key == member_key) else {
+        return Some(source.to_string());
+    };
+
+    let member = &object.members[index];
+    let (start, end) = if index > 0 {
+        // Take the preceding comma along with the member
+        (object.members[index - 1].value_end, member.value_end)
+    } else if let Some(next) = object.members.get(1) {
+        (member.key_start, next.key_start)
+    } else {
+        // Last member: collapse to an empty object
+        (object.open + 1, object.close)
+    };
+    Some(splice(source, start, end, ""))
+}
+
+/// Replace the member's value if present, otherwise append a new member
+fn set_member(
+    source: &str,
+    object: &ObjectSpan,
+    key: &str,
+    value_text: &str,
+    style: &Style,
+) -> String {
+    if let Some(member) = object.member(key) {
+        return splice(source, member.value_start, member.value_end, value_text);
+    }
+
+    let key_text = serde_json::to_string(key).unwrap_or_else(|_| format!("\"{}\"", key));
+    let separator = object
+        .members
+        .first()
+        .map(|m| source[m.key_end..m.value_start].to_string())
+        .unwrap_or_else(|| ": ".to_string());
+
+    match (object.members.last(), object.member_indent(source)) {
+        // Multi-line object: new line after the last member, same indentation
+        (Some(last), Some(indent)) => {
+            let insert = format!(
+                ",{}{}{}{}{}",
+                style.newline, indent, key_text, separator, value_text
+            );
+            splice(source, last.value_end, last.value_end, &insert)
+        }
+        // Single-line object: append inline
+        (Some(last), None) => {
+            let insert = format!(", {}{}{}", key_text, separator, value_text);
+            splice(source, last.value_end, last.value_end, &insert)
+        }
+        // Empty object: expand it onto its own lines
+        (None, _) => {
+            let outer = line_indent(source, object.open);
+            let body = format!(
+                "{nl}{outer}{unit}{key}{sep}{value}{nl}{outer}",
+                nl = style.newline,
+                outer = outer,
+                unit = style.unit,
+                key = key_text,
+                sep = separator,
+                value = value_text,
+            );
+            splice(source, object.open + 1, object.close, &body)
+        }
+    }
+}
+
+fn splice(source: &str, start: usize, end: usize, text: &str) -> String {
+    let mut out = String::with_capacity(source.len() + text.len());
+    out.push_str(&source[..start]);
+    out.push_str(text);
+    out.push_str(&source[end..]);
+    out
+}
+
+/// Leading whitespace of the line containing `pos`
+fn line_indent(source: &str, pos: usize) -> String {
+    let line_start = source[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    source[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// Formatting conventions detected from the document
+struct Style {
+    newline: &'static str,
+    unit: String,
+}
+
+impl Style {
+    fn detect(source: &str, root: &ObjectSpan) -> Self {
+        let newline = if source.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let unit = root
+            .member_indent(source)
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or_else(|| "  ".to_string());
+        Self { newline, unit }
+    }
+
+    /// `{ "key": value }` laid out one level deeper than `indent`
+    fn nested_object(&self, indent: &str, key: &str, value_text: &str) -> String {
+        let key_text = serde_json::to_string(key).unwrap_or_else(|_| format!("\"{}\"", key));
+        format!(
+            "{{{nl}{indent}{unit}{key}: {value}{nl}{indent}}}",
+            nl = self.newline,
+            indent = indent,
+            unit = self.unit,
+            key = key_text,
+            value = value_text,
+        )
+    }
+}
+
+struct Member {
+    key: String,
+    key_start: usize,
+    key_end: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+struct ObjectSpan {
+    open: usize,
+    close: usize,
+    members: Vec<Member>,
+}
+
+impl ObjectSpan {
+    /// Last member with this key (serde semantics for duplicate keys)
+    fn member(&self, key: &str) -> Option<&Member> {
+        self.members.iter().rev().find(|m| m.key == key)
+    }
+
+    /// Indentation of the first member when members sit on their own lines
+    fn member_indent(&self, source: &str) -> Option<String> {
+        let first = self.members.first()?;
+        let before = &source[self.open + 1..first.key_start];
+        if before.contains('\n') {
+            Some(line_indent(source, first.key_start))
+        } else {
+            None
+        }
+    }
+}
+
+/// Minimal JSON scanner that records byte spans. Only used on documents that
+/// serde_json has already accepted, so it does not report detailed errors.
+struct Scanner<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(src: &'a str) -> Self {
+        Self::at(src, 0)
+    }
+
+    fn at(src: &'a str, pos: usize) -> Self {
+        Self {
+            src,
+            bytes: src.as_bytes(),
+            pos,
+        }
+    }
+
+    fn root_object(mut self) -> Option<ObjectSpan> {
+        self.skip_ws();
+        self.object()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn object(&mut self) -> Option<ObjectSpan> {
+        let open = self.pos;
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+
+        loop {
+            self.skip_ws();
+            if self.peek() == Some(b'}') {
+                break;
+            }
+
+            let key_start = self.pos;
+            self.string()?;
+            let key_end = self.pos;
+            let key: String = serde_json::from_str(&self.src[key_start..key_end]).ok()?;
+
+            self.skip_ws();
+            self.expect(b':')?;
+            self.skip_ws();
+
+            let value_start = self.pos;
+            self.value()?;
+            let value_end = self.pos;
+
+            members.push(Member {
+                key,
+                key_start,
+                key_end,
+                value_start,
+                value_end,
+            });
+
+            self.skip_ws();
+            if self.peek() == Some(b',') {
+                self.pos += 1;
+            }
+        }
+
+        let close = self.pos;
+        self.expect(b'}')?;
+        Some(ObjectSpan {
+            open,
+            close,
+            members,
+        })
+    }
+
+    fn string(&mut self) -> Option<()> {
+        self.expect(b'"')?;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.string(),
+            b'{' | b'[' => self.nested(),
+            _ => {
+                while matches!(self.peek(), Some(b) if !matches!(b, b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r'))
+                {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// Skip a bracketed value, tracking depth and ignoring brackets inside strings
+    fn nested(&mut self) -> Option<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek()? {
+                b'"' => {
+                    self.string()?;
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += 1;
+                        return Some(());
+                    }
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(source: &str, key: &str, value: bool) -> String {
+        set_nested_member(source, "enabledPlugins", key, &Value::Bool(value)).unwrap()
+    }
+
+    #[test]
+    fn test_replace_existing_value_only() {
+        let source = "{\n    \"model\": \"opus\",\n    \"enabledPlugins\": {\n        \"b@m\": true,\n        \"a@m\": true\n    }\n}\n";
+        let edited = set(source, "b@m", false);
+        assert_eq!(
+            edited,
+            "{\n    \"model\": \"opus\",\n    \"enabledPlugins\": {\n        \"b@m\": false,\n        \"a@m\": true\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_append_member_keeps_indentation() {
+        let source = "{\n\t\"enabledPlugins\": {\n\t\t\"a@m\": true\n\t},\n\t\"zeta\": 1\n}";
+        let edited = set(source, "c@m", true);
+        assert_eq!(
+            edited,
+            "{\n\t\"enabledPlugins\": {\n\t\t\"a@m\": true,\n\t\t\"c@m\": true\n\t},\n\t\"zeta\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn test_expand_empty_object() {
+        let source = "{\n  \"enabledPlugins\": {},\n  \"other\": [1, 2]\n}\n";
+        let edited = set(source, "a@m", true);
+        assert_eq!(
+            edited,
+            "{\n  \"enabledPlugins\": {\n    \"a@m\": true\n  },\n  \"other\": [1, 2]\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_create_missing_enabled_plugins() {
+        let source = "{\n  \"z\": {\"nested\": \"}\"},\n  \"a\": true\n}\n";
+        let edited = set(source, "x@m", false);
+        assert_eq!(
+            edited,
+            "{\n  \"z\": {\"nested\": \"}\"},\n  \"a\": true,\n  \"enabledPlugins\": {\n    \"x@m\": false\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_single_line_document() {
+        let source = r#"{"enabledPlugins":{"a@m":true}}"#;
+        assert_eq!(
+            set(source, "b@m", false),
+            r#"{"enabledPlugins":{"a@m":true, "b@m":false}}"#
+        );
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let source = "{\r\n  \"enabledPlugins\": {\r\n    \"a@m\": true\r\n  }\r\n}\r\n";
+        assert_eq!(
+            set(source, "b@m", true),
+            "{\r\n  \"enabledPlugins\": {\r\n    \"a@m\": true,\r\n    \"b@m\": true\r\n  }\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_escaped_keys_and_strings() {
+        let source = "{\n  \"say\": \"a \\\"quoted\\\" {\",\n  \"enabledPlugins\": {\n    \"a@m\": false\n  }\n}";
+        let edited = set(source, "a@m", true);
+        assert!(edited.contains("\"say\": \"a \\\"quoted\\\" {\""));
+        assert!(edited.contains("\"a@m\": true"));
+    }
+
+    #[test]
+    fn test_invalid_json_returns_none() {
+        assert!(
+            set_nested_member("{ not json", "enabledPlugins", "a", &Value::Bool(true)).is_none()
+        );
+        assert!(set_nested_member("[1, 2]", "enabledPlugins", "a", &Value::Bool(true)).is_none());
+    }
+
+    #[test]
+    fn test_set_top_level_member() {
+        let source = "{\n  \"enabledMcpjsonServers\": [\"a\"],\n  \"model\": \"opus\"\n}\n";
+        let list = serde_json::json!(["a", "b"]);
+        assert_eq!(
+            set_top_level_member(source, "enabledMcpjsonServers", &list).unwrap(),
+            "{\n  \"enabledMcpjsonServers\": [\"a\",\"b\"],\n  \"model\": \"opus\"\n}\n"
+        );
+        assert_eq!(
+            set_top_level_member(source, "disabledMcpjsonServers", &list).unwrap(),
+            "{\n  \"enabledMcpjsonServers\": [\"a\"],\n  \"model\": \"opus\",\n  \"disabledMcpjsonServers\": [\"a\",\"b\"]\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_nested_member() {
+        let source = "{\n  \"enabledPlugins\": {\n    \"a@m\": true,\n    \"b@m\": false\n  }\n}\n";
+        assert_eq!(
+            remove_nested_member(source, "enabledPlugins", "b@m").unwrap(),
+            "{\n  \"enabledPlugins\": {\n    \"a@m\": true\n  }\n}\n"
+        );
+        assert_eq!(
+            remove_nested_member(source, "enabledPlugins", "a@m").unwrap(),
+            "{\n  \"enabledPlugins\": {\n    \"b@m\": false\n  }\n}\n"
+        );
+        assert_eq!(
+            remove_nested_member(
+                r#"{"enabledPlugins":{"a@m":true}}"#,
+                "enabledPlugins",
+                "a@m"
+            )
+            .unwrap(),
+            r#"{"enabledPlugins":{}}"#
+        );
+        assert_eq!(
+            remove_nested_member(source, "enabledPlugins", "c@m").unwrap(),
+            source
+        );
+    }
+}
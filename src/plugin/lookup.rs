@@ -0,0 +1,236 @@
+use super::{ContentItem, ContentKind, Plugin};
+use std::collections::BTreeMap;
+
+/// A command, agent or skill found in a plugin by `find_providers`
+#[derive(Debug, Clone, Copy)]
+pub struct Provider<'a> {
+    pub plugin: &'a Plugin,
+    pub kind: ContentKind,
+    pub item: &'a ContentItem,
+}
+
+/// Find every plugin that provides a command, agent or skill called `name`.
+///
+/// Accepts the forms users type in a session: `review`, `/review`, and the
+/// plugin-namespaced `/my-plugin:review`. Matching ignores ASCII case.
+pub fn find_providers<'a>(plugins: &'a [Plugin], name: &str) -> Vec<Provider<'a>> {
+    let query = name.trim().trim_start_matches('/');
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut providers = Vec::new();
+    for plugin in plugins {
+        // "/plugin:cmd" only matches within that plugin
+        let unqualified = query
+            .split_once(':')
+            .filter(|(prefix, _)| prefix.eq_ignore_ascii_case(&plugin.name))
+            .map(|(_, rest)| rest);
+
+        for (kind, item) in plugin.contents.items() {
+            let matches = item.name.eq_ignore_ascii_case(query)
+                || unqualified.is_some_and(|rest| item.name.eq_ignore_ascii_case(rest));
+            if matches {
+                providers.push(Provider { plugin, kind, item });
+            }
+        }
+    }
+    providers
+}
+
+/// A command, agent, skill or MCP server name defined by more than one active plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub kind: ContentKind,
+    pub name: String,
+    pub plugin_ids: Vec<String>,
+}
+
+impl Collision {
+    pub fn involves(&self, plugin_id: &str) -> bool {
+        self.plugin_ids.iter().any(|id| id == plugin_id)
+    }
+
+    /// The other plugins sharing the name with `plugin_id`
+    pub fn others<'a>(&'a self, plugin_id: &'a str) -> impl Iterator<Item = &'a str> {
+        self.plugin_ids
+            .iter()
+            .map(String::as_str)
+            .filter(move |id| *id != plugin_id)
+    }
+}
+
+/// Names defined by two or more plugins that are active in the current project
+/// (see `Plugin::is_active`). Names are compared ignoring ASCII case.
+pub fn find_collisions(plugins: &[Plugin]) -> Vec<Collision> {
+    let mut owners: BTreeMap<(ContentKind, String), Collision> = BTreeMap::new();
+
+    for plugin in plugins.iter().filter(|p| p.is_active()) {
+        for (kind, name) in plugin.contents.names() {
+            let entry = owners
+                .entry((kind, name.to_ascii_lowercase()))
+                .or_insert_with(|| Collision {
+                    kind,
+                    name: name.to_string(),
+                    plugin_ids: Vec::new(),
+                });
+            // A plugin listing the same name twice does not collide with itself
+            if !entry.involves(&plugin.id) {
+                entry.plugin_ids.push(plugin.id.clone());
+            }
+        }
+    }
+
+    owners
+        .into_values()
+        .filter(|c| c.plugin_ids.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{McpServerItem, PluginContents, Scope};
+    use std::path::PathBuf;
+
+    fn item(name: &str) -> ContentItem {
+        ContentItem {
+            name: name.to_string(),
+            description: None,
+            path: PathBuf::from(format!("/plugins/{}.md", name)),
+        }
+    }
+
+    fn plugin(name: &str, contents: PluginContents) -> Plugin {
+        Plugin {
+            id: format!("{}@market", name),
+            name: name.to_string(),
+            marketplace: "market".to_string(),
+            marketplace_source: None,
+            description: None,
+            version: None,
+            author: None,
+            homepage: None,
+            repository: None,
+            license: None,
+            keywords: Vec::new(),
+            contents,
+            install_scope: Scope::User,
+            install_path: None,
+            project_path: None,
+            is_current_project: true,
+            enabled_user: Some(true),
+            enabled_project: None,
+            enabled_local: None,
+            installed_at: None,
+            last_updated: None,
+            is_dev: false,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn sample() -> Vec<Plugin> {
+        vec![
+            plugin(
+                "alpha",
+                PluginContents {
+                    commands: vec![item("review"), item("git:commit")],
+                    ..Default::default()
+                },
+            ),
+            plugin(
+                "beta",
+                PluginContents {
+                    commands: vec![item("Review")],
+                    agents: vec![item("reviewer")],
+                    ..Default::default()
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_find_in_every_plugin() {
+        let plugins = sample();
+        let found = find_providers(&plugins, "/review");
+        let ids: Vec<_> = found.iter().map(|p| p.plugin.id.as_str()).collect();
+        assert_eq!(ids, vec!["alpha@market", "beta@market"]);
+        assert!(found.iter().all(|p| p.kind == ContentKind::Command));
+    }
+
+    #[test]
+    fn test_find_namespaced_name() {
+        let plugins = sample();
+        let found = find_providers(&plugins, "/beta:review");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].plugin.name, "beta");
+
+        // Subdirectory commands keep their own colon
+        let found = find_providers(&plugins, "git:commit");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].item.name, "git:commit");
+        assert_eq!(find_providers(&plugins, "/alpha:git:commit").len(), 1);
+    }
+
+    #[test]
+    fn test_find_agent_and_missing() {
+        let plugins = sample();
+        let found = find_providers(&plugins, "reviewer");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ContentKind::Agent);
+
+        assert!(find_providers(&plugins, "deploy").is_empty());
+        assert!(find_providers(&plugins, "/").is_empty());
+    }
+
+    fn server(name: &str) -> McpServerItem {
+        McpServerItem {
+            name: name.to_string(),
+            server: serde_json::from_value(serde_json::json!({ "command": "npx" })).unwrap(),
+            path: PathBuf::from("/plugins/.mcp.json"),
+        }
+    }
+
+    #[test]
+    fn test_collisions_between_active_plugins() {
+        let mut plugins = sample();
+        plugins[0].contents.mcp_servers.push(server("github"));
+        plugins[1].contents.mcp_servers.push(server("github"));
+
+        let collisions = find_collisions(&plugins);
+        assert_eq!(collisions.len(), 2);
+        assert_eq!(collisions[0].kind, ContentKind::Command);
+        assert_eq!(collisions[0].name, "review");
+        assert_eq!(
+            collisions[0].plugin_ids,
+            vec!["alpha@market", "beta@market"]
+        );
+        assert_eq!(collisions[1].kind, ContentKind::McpServer);
+        assert_eq!(
+            collisions[1].others("alpha@market").collect::<Vec<_>>(),
+            vec!["beta@market"]
+        );
+    }
+
+    #[test]
+    fn test_collisions_ignore_inactive_plugins() {
+        let mut plugins = sample();
+        plugins[1].enabled_user = Some(false);
+        assert!(find_collisions(&plugins).is_empty());
+
+        let mut plugins = sample();
+        plugins[1].install_scope = Scope::Local;
+        plugins[1].enabled_local = Some(true);
+        plugins[1].is_current_project = false;
+        assert!(find_collisions(&plugins).is_empty());
+    }
+
+    #[test]
+    fn test_same_name_different_kind_is_not_a_collision() {
+        let mut plugins = sample();
+        plugins[1].contents.commands.clear();
+        plugins[0].contents.agents.push(item("review"));
+        plugins[1].contents.skills.push(item("review"));
+        assert!(find_collisions(&plugins).is_empty());
+    }
+}
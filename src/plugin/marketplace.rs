@@ -0,0 +1,142 @@
+//! Registering marketplaces (`ccpm marketplace add`).
+//!
+//! A local directory is registered in place. Anything else is cloned under
+//! `~/.claude/plugins/marketplaces/<name>`, the layout Claude Code uses for its own
+//! checkouts. The name comes from the catalog unless one is given.
+
+use super::{
+    config::{MarketplaceEntry, MarketplaceManifest, MarketplaceSource},
+    updates::{check_ref, git_output},
+    Result,
+};
+use std::path::{Path, PathBuf};
+
+/// A registered marketplace with its catalog and what is installed from it
+#[derive(Debug, Clone)]
+pub struct MarketplaceSummary {
+    pub name: String,
+    pub entry: MarketplaceEntry,
+    pub head: Option<String>,                 // Commit of a git checkout
+    pub catalog: Option<MarketplaceManifest>, // None when marketplace.json is missing
+    pub installed: Vec<String>,               // Plugin names, sorted
+}
+
+impl MarketplaceSummary {
+    /// Plugins listed in the catalog
+    pub fn available_count(&self) -> usize {
+        self.catalog.as_ref().map_or(0, |c| c.plugins.len())
+    }
+
+    pub fn is_installed(&self, plugin: &str) -> bool {
+        self.installed.iter().any(|name| name == plugin)
+    }
+}
+
+/// A marketplace declared in the project's `extraKnownMarketplaces` that is not
+/// registered in known_marketplaces.json yet
+#[derive(Debug, Clone)]
+pub struct PendingMarketplace {
+    pub name: String,
+    pub source: MarketplaceSource,
+    pub declared_in: PathBuf, // The settings file declaring it
+}
+
+/// Interpret `ccpm marketplace add <source>`: an existing directory, a GitHub
+/// `owner/repo`, or any other git URL
+pub fn parse_marketplace_source(arg: &str) -> MarketplaceSource {
+    let path = Path::new(arg);
+    if path.is_dir() {
+        return MarketplaceSource::Directory {
+            path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+            other: Default::default(),
+        };
+    }
+    if is_github_shorthand(arg) {
+        return MarketplaceSource::Github {
+            repo: arg.to_string(),
+            other: Default::default(),
+        };
+    }
+    MarketplaceSource::Git {
+        url: arg.to_string(),
+        other: Default::default(),
+    }
+}
+
+fn is_github_shorthand(arg: &str) -> bool {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match arg.split_once('/') {
+        Some((owner, repo)) => valid(owner) && valid(repo) && !repo.ends_with(".git"),
+        None => false,
+    }
+}
+
+/// URL to clone a marketplace from; None for directories, which are used in place
+pub fn clone_url(source: &MarketplaceSource) -> Option<String> {
+    match source {
+        MarketplaceSource::Github { repo, .. } => Some(format!("https://github.com/{}.git", repo)),
+        MarketplaceSource::Git { url, .. } => Some(url.clone()),
+        MarketplaceSource::Directory { .. } | MarketplaceSource::Unknown(_) => None,
+    }
+}
+
+/// Clone `url` into `dest`, whose parent must exist, at a branch or tag if given
+pub(crate) fn clone_checkout(url: &str, git_ref: Option<&str>, dest: &Path) -> Result<()> {
+    let parent = dest.parent().unwrap_or(dest);
+    let dest = dest.to_string_lossy();
+    let mut args = vec!["clone", "--quiet"];
+    if let Some(git_ref) = git_ref {
+        check_ref(git_ref)?;
+        args.extend(["--branch", git_ref]);
+    }
+    args.extend(["--", url, &dest]);
+    git_output(parent, &args).map(|_| ())
+}
+
+/// An RFC 3339 timestamp such as `lastUpdated` in local time, or as written if it
+/// does not parse
+pub fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_marketplace_source() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().to_string_lossy().to_string();
+        assert_eq!(parse_marketplace_source(&dir).kind(), "directory");
+
+        let github = parse_marketplace_source("anthropics/claude-plugins");
+        assert_eq!(github.kind(), "github");
+        assert_eq!(
+            clone_url(&github).as_deref(),
+            Some("https://github.com/anthropics/claude-plugins.git")
+        );
+
+        for url in [
+            "https://git.example.com/team/plugins.git",
+            "git@github.com:team/plugins.git",
+            "file:///srv/git/plugins.git",
+            "team/plugins.git",
+        ] {
+            let source = parse_marketplace_source(url);
+            assert_eq!(source.kind(), "git", "{}", url);
+            assert_eq!(clone_url(&source).as_deref(), Some(url));
+        }
+    }
+}
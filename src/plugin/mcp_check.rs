@@ -0,0 +1,378 @@
+//! Local handshake check for stdio MCP servers.
+//!
+//! Spawns the server the way Claude Code would, sends `initialize` and `tools/list`
+//! as newline-delimited JSON-RPC over stdin/stdout, and reports what came back.
+
+use super::McpServerItem;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Lines of server stderr kept for the report
+const STDERR_LINES: usize = 20;
+
+/// Outcome of checking one MCP server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct McpCheckReport {
+    pub server: String,
+    /// Command line after `${...}` expansion
+    pub command_line: String,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub tools: Vec<String>,
+    /// `${VAR}` references with no value in the environment and no default
+    pub missing_env: Vec<String>,
+    pub error: Option<String>,
+    /// Tail of the server's stderr
+    pub stderr: Vec<String>,
+}
+
+impl McpCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Start a stdio server from a plugin and run the `initialize` + `tools/list` handshake.
+/// `timeout` bounds the whole exchange; the server is killed afterwards.
+pub fn check_stdio_server(
+    item: &McpServerItem,
+    plugin_root: &Path,
+    timeout: Duration,
+) -> McpCheckReport {
+    let mut report = McpCheckReport {
+        server: item.name.clone(),
+        ..Default::default()
+    };
+
+    let Some(ref command) = item.server.command else {
+        report.error = Some(format!(
+            "only stdio servers can be checked ({} transport)",
+            item.transport()
+        ));
+        return report;
+    };
+
+    let root = plugin_root.to_string_lossy().to_string();
+    let vars: HashMap<String, String> = std::env::vars().collect();
+    let mut missing = Vec::new();
+    let program = expand_vars(command, &root, &vars, &mut missing);
+    let args: Vec<String> = item
+        .server
+        .args
+        .iter()
+        .map(|a| expand_vars(a, &root, &vars, &mut missing))
+        .collect();
+    let env: HashMap<String, String> = item
+        .server
+        .env
+        .iter()
+        .map(|(k, v)| (k.clone(), expand_vars(v, &root, &vars, &mut missing)))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    report.missing_env = missing;
+    report.command_line = std::iter::once(program.as_str())
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let spawned = Command::new(&program)
+        .args(&args)
+        .envs(&env)
+        .env("CLAUDE_PLUGIN_ROOT", &root)
+        .current_dir(plugin_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            report.error = Some(format!("failed to start {}: {}", program, e));
+            return report;
+        }
+    };
+
+    let stderr = collect_stderr(&mut child);
+    let mut session = match Session::start(&mut child, Instant::now() + timeout) {
+        Some(session) => session,
+        None => {
+            report.error = Some("server has no stdin/stdout".to_string());
+            return report;
+        }
+    };
+
+    let result = session.handshake(&mut report);
+    drop(session);
+    let _ = child.kill();
+    let status = child.wait().ok();
+
+    if let Err(error) = result {
+        report.error = Some(match status {
+            Some(status) if error.starts_with("server exited") => {
+                format!("{} ({})", error, status)
+            }
+            _ => error,
+        });
+    }
+    report.stderr = stderr
+        .recv_timeout(Duration::from_millis(500))
+        .unwrap_or_default();
+    report
+}
+
+/// Expand `${CLAUDE_PLUGIN_ROOT}`, `${VAR}` and `${VAR:-default}` like Claude Code does
+/// for `.mcp.json` values, looking variables up in `vars`. Unset variables without a
+/// default expand to "" and are recorded in `missing`.
+pub fn expand_vars(
+    value: &str,
+    plugin_root: &str,
+    vars: &HashMap<String, String>,
+    missing: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let expr = &after[..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+        if name == "CLAUDE_PLUGIN_ROOT" {
+            out.push_str(plugin_root);
+        } else {
+            match (vars.get(name).filter(|v| !v.is_empty()), default) {
+                (Some(v), _) => out.push_str(v),
+                (None, Some(default)) => out.push_str(default),
+                (None, None) => missing.push(name.to_string()),
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Read stderr on a thread; the tail is sent once the pipe closes
+fn collect_stderr(child: &mut Child) -> Receiver<Vec<String>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut tail: Vec<String> = Vec::new();
+            for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                if tail.len() == STDERR_LINES {
+                    tail.remove(0);
+                }
+                tail.push(line);
+            }
+            let _ = tx.send(tail);
+        });
+    }
+    rx
+}
+
+/// JSON-RPC over the child's stdin/stdout, bounded by a deadline
+struct Session {
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    deadline: Instant,
+}
+
+impl Session {
+    fn start(child: &mut Child, deadline: Instant) -> Option<Self> {
+        let stdin = child.stdin.take()?;
+        let stdout = child.stdout.take()?;
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Some(Self {
+            stdin,
+            lines,
+            deadline,
+        })
+    }
+
+    fn handshake(&mut self, report: &mut McpCheckReport) -> Result<(), String> {
+        let init = self.request(
+            1,
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "ccpm", "version": env!("CARGO_PKG_VERSION") }
+            }),
+        )?;
+        report.protocol_version = init["protocolVersion"].as_str().map(String::from);
+        report.server_name = init["serverInfo"]["name"].as_str().map(String::from);
+        report.server_version = init["serverInfo"]["version"].as_str().map(String::from);
+
+        self.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))?;
+
+        // tools/list is paginated through nextCursor
+        let mut cursor: Option<String> = None;
+        for id in 2.. {
+            let params = match cursor {
+                Some(ref cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request(id, "tools/list", params)?;
+            report.tools.extend(
+                page["tools"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t["name"].as_str().map(String::from)),
+            );
+            cursor = page["nextCursor"].as_str().map(String::from);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| "server exited before reading the request".to_string())
+    }
+
+    /// Send a request and wait for the response with the same id, skipping
+    /// notifications and anything that is not JSON
+    fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!("timed out waiting for the {} response", method))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(format!("server exited before answering {}", method))
+                }
+            };
+
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(format!(
+                    "{} failed: {}",
+                    method,
+                    error["message"].as_str().unwrap_or("unknown error")
+                ));
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_vars() {
+        let mut missing = Vec::new();
+        let vars = HashMap::from([
+            ("CCPM_TEST_EXPAND".to_string(), "value".to_string()),
+            ("CCPM_TEST_EMPTY".to_string(), String::new()),
+        ]);
+
+        assert_eq!(
+            expand_vars(
+                "${CLAUDE_PLUGIN_ROOT}/bin/server",
+                "/plugins/p",
+                &vars,
+                &mut missing
+            ),
+            "/plugins/p/bin/server"
+        );
+        assert_eq!(
+            expand_vars("--key=${CCPM_TEST_EXPAND}", "/p", &vars, &mut missing),
+            "--key=value"
+        );
+        assert_eq!(
+            expand_vars(
+                "${CCPM_TEST_UNSET_VAR:-fallback}",
+                "/p",
+                &vars,
+                &mut missing
+            ),
+            "fallback"
+        );
+        assert_eq!(
+            expand_vars("${CCPM_TEST_EMPTY:-fallback}", "/p", &vars, &mut missing),
+            "fallback"
+        );
+        assert!(missing.is_empty());
+
+        assert_eq!(
+            expand_vars("Bearer ${CCPM_TEST_UNSET_VAR}", "/p", &vars, &mut missing),
+            "Bearer "
+        );
+        assert_eq!(missing, vec!["CCPM_TEST_UNSET_VAR"]);
+
+        // Unterminated references are left alone
+        assert_eq!(expand_vars("${OOPS", "/p", &vars, &mut missing), "${OOPS");
+    }
+
+    #[test]
+    fn test_http_server_is_not_checked() {
+        let item = McpServerItem {
+            name: "docs".to_string(),
+            server: serde_json::from_value(json!({ "type": "http", "url": "https://x" })).unwrap(),
+            path: "/p/.mcp.json".into(),
+        };
+        let report = check_stdio_server(&item, Path::new("/p"), DEFAULT_CHECK_TIMEOUT);
+        assert!(!report.is_ok());
+        assert!(report.error.unwrap().contains("http transport"));
+    }
+
+    #[test]
+    fn test_missing_command_reports_startup_failure() {
+        let item = McpServerItem {
+            name: "gone".to_string(),
+            server: serde_json::from_value(json!({ "command": "ccpm-no-such-server-binary" }))
+                .unwrap(),
+            path: "/p/.mcp.json".into(),
+        };
+        let temp = tempfile::TempDir::new().unwrap();
+        let report = check_stdio_server(&item, temp.path(), DEFAULT_CHECK_TIMEOUT);
+        assert!(report
+            .error
+            .unwrap()
+            .starts_with("failed to start ccpm-no-such-server-binary"));
+    }
+}
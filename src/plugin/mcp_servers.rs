@@ -0,0 +1,215 @@
+//! MCP servers Claude Code would load in the current project.
+//!
+//! Servers come from enabled plugins, the project's `.mcp.json` and `~/.claude.json`
+//! (user-wide `mcpServers` plus the per-project `projects[<cwd>].mcpServers`).
+//! Project `.mcp.json` servers are shared through git, so Claude Code only starts
+//! them once they are approved via `enabledMcpjsonServers`.
+
+use super::{config::Settings, McpServer, Scope};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Where an MCP server definition came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpOrigin {
+    /// Bundled with an enabled plugin (plugin id)
+    Plugin(String),
+    /// The project's `.mcp.json`
+    Project(PathBuf),
+    /// `mcpServers` in `~/.claude.json`
+    User(PathBuf),
+    /// `projects[<cwd>].mcpServers` in `~/.claude.json`
+    Local(PathBuf),
+}
+
+impl McpOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            McpOrigin::Plugin(_) => "plugin",
+            McpOrigin::Project(_) => "project",
+            McpOrigin::User(_) => "user",
+            McpOrigin::Local(_) => "local",
+        }
+    }
+}
+
+impl fmt::Display for McpOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            McpOrigin::Plugin(id) => write!(f, "{}", id),
+            McpOrigin::Project(path) | McpOrigin::User(path) | McpOrigin::Local(path) => {
+                write!(f, "{}", path.display())
+            }
+        }
+    }
+}
+
+/// Approval state of a project `.mcp.json` server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpApproval {
+    /// Plugin, user and local servers are trusted as-is
+    NotRequired,
+    /// Not listed in any settings file; Claude Code asks on startup
+    Pending,
+    /// Listed in `enabledMcpjsonServers` or covered by `enableAllProjectMcpServers`
+    Approved(Scope),
+    /// Listed in `disabledMcpjsonServers`
+    Denied(Scope),
+}
+
+impl fmt::Display for McpApproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            McpApproval::NotRequired => write!(f, "not required"),
+            McpApproval::Pending => write!(f, "pending"),
+            McpApproval::Approved(scope) => write!(f, "approved ({})", scope),
+            McpApproval::Denied(scope) => write!(f, "denied ({})", scope),
+        }
+    }
+}
+
+/// Settings files that can hold MCP approvals, in precedence order (local wins)
+#[derive(Debug, Clone, Default)]
+pub struct McpApprovalSettings {
+    pub local: Option<Settings>,
+    pub project: Option<Settings>,
+    pub user: Option<Settings>,
+}
+
+impl McpApprovalSettings {
+    /// Approval for a project `.mcp.json` server. The most specific scope that lists
+    /// the server decides; `enableAllProjectMcpServers` covers the rest.
+    pub fn approval(&self, name: &str) -> McpApproval {
+        let scopes = [
+            (Scope::Local, &self.local),
+            (Scope::Project, &self.project),
+            (Scope::User, &self.user),
+        ];
+
+        for (scope, settings) in scopes.iter() {
+            let Some(settings) = settings else { continue };
+            if settings.disabled_mcpjson_servers.iter().any(|s| s == name) {
+                return McpApproval::Denied(*scope);
+            }
+            if settings.enabled_mcpjson_servers.iter().any(|s| s == name) {
+                return McpApproval::Approved(*scope);
+            }
+        }
+
+        for (scope, settings) in scopes.iter() {
+            if let Some(all) = settings
+                .as_ref()
+                .and_then(|s| s.enable_all_project_mcp_servers)
+            {
+                return if all {
+                    McpApproval::Approved(*scope)
+                } else {
+                    McpApproval::Pending
+                };
+            }
+        }
+        McpApproval::Pending
+    }
+}
+
+/// One MCP server as Claude Code would see it
+#[derive(Debug, Clone)]
+pub struct McpServerEntry {
+    pub name: String,
+    pub origin: McpOrigin,
+    pub server: McpServer,
+    pub approval: McpApproval,
+}
+
+impl McpServerEntry {
+    /// Env keys with their values hidden, e.g. `GITHUB_TOKEN=***`
+    pub fn redacted_env(&self) -> Vec<String> {
+        redacted(self.server.env.keys())
+    }
+
+    /// Header names with their values hidden
+    pub fn redacted_headers(&self) -> Vec<String> {
+        redacted(self.server.headers.keys())
+    }
+
+    /// Whether approvals in settings apply to this server
+    pub fn needs_approval(&self) -> bool {
+        matches!(self.origin, McpOrigin::Project(_))
+    }
+
+    /// Whether Claude Code would start this server
+    pub fn is_active(&self) -> bool {
+        !matches!(self.approval, McpApproval::Pending | McpApproval::Denied(_))
+    }
+}
+
+fn redacted<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut keys: Vec<String> = keys.map(|k| format!("{}=***", k)).collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings(value: serde_json::Value) -> Option<Settings> {
+        Some(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn test_approval_precedence() {
+        let approvals = McpApprovalSettings {
+            local: settings(json!({ "disabledMcpjsonServers": ["github"] })),
+            project: settings(json!({ "enabledMcpjsonServers": ["github", "docs"] })),
+            user: None,
+        };
+        assert_eq!(
+            approvals.approval("github"),
+            McpApproval::Denied(Scope::Local)
+        );
+        assert_eq!(
+            approvals.approval("docs"),
+            McpApproval::Approved(Scope::Project)
+        );
+        assert_eq!(approvals.approval("other"), McpApproval::Pending);
+    }
+
+    #[test]
+    fn test_enable_all_project_servers() {
+        let approvals = McpApprovalSettings {
+            local: settings(json!({ "disabledMcpjsonServers": ["github"] })),
+            project: None,
+            user: settings(json!({ "enableAllProjectMcpServers": true })),
+        };
+        assert_eq!(
+            approvals.approval("github"),
+            McpApproval::Denied(Scope::Local)
+        );
+        assert_eq!(
+            approvals.approval("docs"),
+            McpApproval::Approved(Scope::User)
+        );
+    }
+
+    #[test]
+    fn test_env_values_are_redacted() {
+        let entry = McpServerEntry {
+            name: "github".to_string(),
+            origin: McpOrigin::Plugin("gh@m".to_string()),
+            server: serde_json::from_value(json!({
+                "command": "gh-mcp",
+                "env": { "GITHUB_TOKEN": "secret", "API_URL": "https://x" }
+            }))
+            .unwrap(),
+            approval: McpApproval::NotRequired,
+        };
+        assert_eq!(
+            entry.redacted_env(),
+            vec!["API_URL=***", "GITHUB_TOKEN=***"]
+        );
+        assert!(entry.is_active());
+        assert!(!entry.needs_approval());
+    }
+}
@@ -1,10 +1,43 @@
+mod audit;
+mod bisect;
+mod budget;
+mod changelog;
 mod config;
+mod contents;
 mod discovery;
+mod expiry;
+mod frontmatter;
+mod json_edit;
+mod lookup;
+mod marketplace;
+mod mcp_check;
+mod mcp_servers;
 mod operations;
-
+mod plan;
+mod run;
+mod scaffold;
+mod updates;
+mod validate;
+
+pub use audit::*;
+pub use bisect::*;
+pub use budget::*;
+pub use changelog::*;
 pub use config::*;
+pub use contents::*;
 pub use discovery::*;
+pub use expiry::*;
+pub use frontmatter::*;
+pub use lookup::*;
+pub use marketplace::*;
+pub use mcp_check::*;
+pub use mcp_servers::*;
 pub use operations::*;
+pub use plan::*;
+pub use run::*;
+pub use scaffold::*;
+pub use updates::*;
+pub use validate::*;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -39,6 +72,15 @@ pub enum PluginError {
     #[error("Marketplace not found: {0}")]
     MarketplaceNotFound(String),
 
+    #[error("Marketplace already registered: {0}")]
+    MarketplaceExists(String),
+
+    #[error("Not a marketplace (no .claude-plugin/marketplace.json): {0}")]
+    NotAMarketplace(PathBuf),
+
+    #[error("Unsupported marketplace source: {0}")]
+    UnsupportedMarketplaceSource(String),
+
     #[error("Failed to acquire file lock: {path}")]
     LockError {
         path: PathBuf,
@@ -49,8 +91,50 @@ pub enum PluginError {
     #[error("Lock file conflict: {path} (held by PID {pid})")]
     LockConflict { path: PathBuf, pid: u32 },
 
+    #[error("Config file changed since the plan was made: {0}")]
+    PlanOutdated(PathBuf),
+
     #[error("Home directory not found")]
     HomeDirNotFound,
+
+    #[error("Invalid name: {0} (use lowercase letters, digits and dashes)")]
+    InvalidName(String),
+
+    #[error("Already exists: {0}")]
+    AlreadyExists(PathBuf),
+
+    #[error("Not a plugin directory (no .claude-plugin/plugin.json): {0}")]
+    NotAPlugin(PathBuf),
+
+    #[error("ccpm run is already active for {path} (PID {pid})")]
+    RunInProgress { path: PathBuf, pid: u32 },
+
+    #[error("A bisect is already in progress for {0} (finish it with ccpm bisect reset)")]
+    BisectInProgress(PathBuf),
+
+    #[error("No bisect in progress (start one with ccpm bisect start)")]
+    NoBisect,
+
+    #[error("git failed in {path}: {message}")]
+    GitFailed { path: PathBuf, message: String },
+
+    #[error("{0} cannot be updated from its marketplace checkout (its source is outside the marketplace repository)")]
+    NotUpdatable(String),
+
+    #[error("Invalid git ref: {0}")]
+    InvalidRef(String),
+
+    #[error("{0} is pinned to {1} (ccpm unpin {0} to allow updates)")]
+    Pinned(String, String),
+
+    #[error("{0} is not pinned")]
+    NotPinned(String),
+
+    #[error("No previous version of {0} to roll back to (one is kept by ccpm update)")]
+    NoPreviousVersion(String),
+
+    #[error("{0} has no recorded commit (gitCommitSha) to compare against")]
+    NoInstalledCommit(String),
 }
 
 pub type Result<T> = std::result::Result<T, PluginError>;
@@ -85,9 +169,15 @@ pub struct Plugin {
     pub id: String,
     pub name: String,
     pub marketplace: String,
+    pub marketplace_source: Option<MarketplaceSource>, // From known_marketplaces.json, if registered
     pub description: Option<String>,
     pub version: Option<String>,
     pub author: Option<Author>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>, // Repository URL
+    pub license: Option<String>,
+    pub keywords: Vec<String>,
+    pub contents: PluginContents, // Commands, agents, skills, hooks and MCP servers on disk
 
     // Installation information
     pub install_scope: Scope, // Where installed (from installed_plugins.json entry.scope)
@@ -104,6 +194,10 @@ pub struct Plugin {
 
     pub installed_at: Option<String>,
     pub last_updated: Option<String>,
+
+    // Development plugins registered with `ccpm dev add`, loaded via `claude --plugin-dir`
+    pub is_dev: bool,
+    pub diagnostics: Vec<Diagnostic>, // Validation results for dev plugins, refreshed on reload
 }
 
 impl Plugin {
@@ -127,6 +221,13 @@ impl Plugin {
         self.enabled_user.unwrap_or(false)
     }
 
+    /// Returns true if the plugin is enabled and loaded in the current project.
+    /// Project/local installs that belong to another project never are, whatever
+    /// their own project's settings say.
+    pub fn is_active(&self) -> bool {
+        self.is_current_project && self.is_enabled()
+    }
+
     /// Human-readable enabled context description
     /// Shows which scopes have explicit settings and their values
     pub fn enabled_context(&self) -> String {
@@ -162,8 +263,11 @@ impl Plugin {
         }
     }
 
-    /// Scope indicator for the list view: [U], [P], [P*], [L], or [L*]
+    /// Scope indicator for the list view: [U], [P], [P*], [L], [L*], or [D] for dev plugins
     pub fn scope_indicator(&self) -> &'static str {
+        if self.is_dev {
+            return "[D]";
+        }
         match (self.install_scope, self.is_current_project) {
             (Scope::User, _) => "[U]",
             (Scope::Project, true) => "[P]",
@@ -173,6 +277,14 @@ impl Plugin {
         }
     }
 
+    /// Validation errors (warnings excluded) found on the last reload
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count()
+    }
+
     pub fn status_indicator(&self) -> &'static str {
         if self.is_enabled() {
             "[+]"
@@ -201,6 +313,7 @@ pub enum ScopeFilter {
     User,
     Project,
     Local,
+    Dev,
 }
 
 impl ScopeFilter {
@@ -209,7 +322,8 @@ impl ScopeFilter {
             ScopeFilter::All => ScopeFilter::User,
             ScopeFilter::User => ScopeFilter::Project,
             ScopeFilter::Project => ScopeFilter::Local,
-            ScopeFilter::Local => ScopeFilter::All,
+            ScopeFilter::Local => ScopeFilter::Dev,
+            ScopeFilter::Dev => ScopeFilter::All,
         }
     }
 
@@ -219,6 +333,18 @@ impl ScopeFilter {
             ScopeFilter::User => "User",
             ScopeFilter::Project => "Project",
             ScopeFilter::Local => "Local",
+            ScopeFilter::Dev => "Dev",
+        }
+    }
+
+    /// Dev plugins only show under All and Dev
+    pub fn matches(&self, plugin: &Plugin) -> bool {
+        match self {
+            ScopeFilter::All => true,
+            ScopeFilter::Dev => plugin.is_dev,
+            ScopeFilter::User => !plugin.is_dev && plugin.install_scope == Scope::User,
+            ScopeFilter::Project => !plugin.is_dev && plugin.install_scope == Scope::Project,
+            ScopeFilter::Local => !plugin.is_dev && plugin.install_scope == Scope::Local,
         }
     }
 }
@@ -244,9 +370,15 @@ mod tests {
             id: "test@marketplace".to_string(),
             name: "test".to_string(),
             marketplace: "marketplace".to_string(),
+            marketplace_source: None,
             description: None,
             version: None,
             author: None,
+            homepage: None,
+            repository: None,
+            license: None,
+            keywords: Vec::new(),
+            contents: PluginContents::default(),
             install_scope: Scope::User,
             install_path: None,
             project_path: None,
@@ -256,9 +388,23 @@ mod tests {
             enabled_local: None,   // No setting
             installed_at: None,
             last_updated: None,
+            is_dev: false,
+            diagnostics: Vec::new(),
         }
     }
 
+    #[test]
+    fn test_plugin_is_active_requires_current_project() {
+        let mut plugin = make_test_plugin();
+        plugin.install_scope = Scope::Local;
+        plugin.enabled_local = Some(true);
+        assert!(plugin.is_active());
+
+        plugin.is_current_project = false;
+        assert!(plugin.is_enabled());
+        assert!(!plugin.is_active());
+    }
+
     #[test]
     fn test_plugin_display_name() {
         let mut plugin = make_test_plugin();
@@ -420,7 +566,8 @@ mod tests {
         assert_eq!(ScopeFilter::All.next(), ScopeFilter::User);
         assert_eq!(ScopeFilter::User.next(), ScopeFilter::Project);
         assert_eq!(ScopeFilter::Project.next(), ScopeFilter::Local);
-        assert_eq!(ScopeFilter::Local.next(), ScopeFilter::All);
+        assert_eq!(ScopeFilter::Local.next(), ScopeFilter::Dev);
+        assert_eq!(ScopeFilter::Dev.next(), ScopeFilter::All);
     }
 
     #[test]
@@ -429,6 +576,7 @@ mod tests {
         assert_eq!(ScopeFilter::User.label(), "User");
         assert_eq!(ScopeFilter::Project.label(), "Project");
         assert_eq!(ScopeFilter::Local.label(), "Local");
+        assert_eq!(ScopeFilter::Dev.label(), "Dev");
     }
 
     #[test]
@@ -1,10 +1,24 @@
 use super::{
-    config::{ConfigPaths, KnownMarketplaces, Settings},
-    Plugin, PluginError, Result, Scope,
+    bisect::{BisectState, BisectVerdict},
+    config::{
+        ConfigPaths, DevPluginEntry, DevPlugins, InstalledPlugins, KnownMarketplaces,
+        MarketplaceEntry, MarketplaceSource, Pin, Pins, PreviousInstalls, Settings,
+    },
+    discovery::load_catalog,
+    expiry::{ExpiringEnable, ExpiringEnables},
+    json_edit,
+    marketplace::{clone_checkout, clone_url, parse_marketplace_source},
+    plan::{ChangePlan, FileChange},
+    run::{marker_is_live, marker_path, RunMarker},
+    updates::{cache_dir_name, copy_dir, fetch_checkout, PluginUpdate},
+    validate::is_kebab_case,
+    Plugin, PluginDiscovery, PluginError, Result, Scope,
 };
 use chrono::{DateTime, Utc};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -34,14 +48,14 @@ impl Drop for LockFileGuard {
 
 /// Check if a process with the given PID is currently running.
 #[cfg(unix)]
-fn is_process_running(pid: u32) -> bool {
+pub(crate) fn is_process_running(pid: u32) -> bool {
     // On Unix, sending signal 0 checks if process exists without sending a signal
     // Returns 0 if process exists (regardless of permissions), -1 with ESRCH if not
     unsafe { libc::kill(pid as i32, 0) == 0 }
 }
 
 #[cfg(not(unix))]
-fn is_process_running(_pid: u32) -> bool {
+pub(crate) fn is_process_running(_pid: u32) -> bool {
     // On non-Unix platforms, conservatively assume the process might be running
     // to avoid accidentally overwriting active locks
     true
@@ -64,25 +78,265 @@ impl PluginService {
 
     /// Enable a plugin in the specified scope
     pub fn enable_plugin(&self, id: &str, scope: Scope) -> Result<()> {
-        self.set_plugin_enabled(id, scope, true)
+        self.set_plugin_enabled(id, scope, None, true)
     }
 
     /// Disable a plugin in the specified scope
     pub fn disable_plugin(&self, id: &str, scope: Scope) -> Result<()> {
-        self.set_plugin_enabled(id, scope, false)
+        self.set_plugin_enabled(id, scope, None, false)
     }
 
     /// Toggle plugin enabled state
     pub fn toggle_plugin(&self, plugin: &Plugin) -> Result<bool> {
         let new_state = !plugin.is_enabled();
-        self.set_plugin_enabled(&plugin.id, plugin.install_scope, new_state)?;
+        self.set_plugin_enabled(&plugin.id, plugin.install_scope, None, new_state)?;
         Ok(new_state)
     }
 
+    /// Set a plugin's enabled state, optionally in another project's settings
+    pub fn set_plugin_enabled(
+        &self,
+        id: &str,
+        scope: Scope,
+        project: Option<&Path>,
+        enabled: bool,
+    ) -> Result<()> {
+        let plan = self.plan_set_enabled(id, scope, project, enabled)?;
+        self.apply(&plan)
+    }
+
+    /// Plan an `enabledPlugins` change without taking locks or writing.
+    /// `project` selects another project's `.claude/` directory; None uses the CWD.
+    pub fn plan_set_enabled(
+        &self,
+        id: &str,
+        scope: Scope,
+        project: Option<&Path>,
+        enabled: bool,
+    ) -> Result<ChangePlan> {
+        let path = self.paths.settings_for(scope, project);
+        let before = read_optional(&path);
+        let after = set_enabled_in(&path, before.as_deref(), id, enabled)?;
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            path: path.clone(),
+            before,
+            after,
+        });
+
+        // An explicit enable or disable replaces a running `--for` timer
+        let state_path = self.paths.expiring();
+        let mut state = self.load_expiring();
+        let count = state.expiring.len();
+        state
+            .expiring
+            .retain(|e| !(e.plugin_id == id && e.settings_path == path));
+        if state.expiring.len() != count {
+            plan.push(FileChange {
+                after: to_json(&state_path, &state)?,
+                before: read_optional(&state_path),
+                path: state_path,
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Plan enabling a plugin until `expires_at`. The value it replaces is kept in
+    /// ccpm's state so `plan_revert_expired` can put it back.
+    pub fn plan_enable_until(
+        &self,
+        id: &str,
+        scope: Scope,
+        project: Option<&Path>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<ChangePlan> {
+        let path = self.paths.settings_for(scope, project);
+        let before = read_optional(&path);
+        let after = set_enabled_in(&path, before.as_deref(), id, true)?;
+
+        let state_path = self.paths.expiring();
+        let state_before = read_optional(&state_path);
+        let mut state = self.load_expiring();
+        // Extending a running timer keeps the value from before the first enable
+        let previous = match state
+            .expiring
+            .iter()
+            .position(|e| e.plugin_id == id && e.settings_path == path)
+        {
+            Some(index) => state.expiring.remove(index).previous,
+            None => enabled_value(before.as_deref(), id),
+        };
+        state.expiring.push(ExpiringEnable {
+            plugin_id: id.to_string(),
+            scope,
+            settings_path: path.clone(),
+            previous,
+            expires_at,
+        });
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            path,
+            before,
+            after,
+        });
+        plan.push(FileChange {
+            after: to_json(&state_path, &state)?,
+            path: state_path,
+            before: state_before,
+        });
+        Ok(plan)
+    }
+
+    /// Plan reverting every time-limited enable that expired by `now`. Returns the
+    /// expired entries with the plan. A plugin disabled or removed in the meantime
+    /// is left as it is; only the record is dropped.
+    pub fn plan_revert_expired(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<(ChangePlan, Vec<ExpiringEnable>)> {
+        let state_path = self.paths.expiring();
+        let state_before = read_optional(&state_path);
+        let mut state = self.load_expiring();
+        let (expired, pending): (Vec<_>, Vec<_>) =
+            state.expiring.drain(..).partition(|e| e.is_expired(now));
+        state.expiring = pending;
+
+        let mut plan = ChangePlan::new();
+        if expired.is_empty() {
+            return Ok((plan, expired));
+        }
+
+        // Entries can share a settings file, so edits are chained per file
+        let mut files: BTreeMap<PathBuf, (Option<String>, Option<String>)> = BTreeMap::new();
+        for entry in &expired {
+            let path = &entry.settings_path;
+            let (_, current) = files.entry(path.clone()).or_insert_with(|| {
+                let content = read_optional(path);
+                (content.clone(), content)
+            });
+            let Some(source) = current.as_deref() else {
+                continue;
+            };
+            if enabled_value(Some(source), &entry.plugin_id) != Some(true) {
+                continue;
+            }
+            *current = Some(match entry.previous {
+                Some(enabled) => set_enabled_in(path, Some(source), &entry.plugin_id, enabled)?,
+                None => remove_enabled_in(path, source, &entry.plugin_id)?,
+            });
+        }
+
+        for (path, (before, after)) in files {
+            if let Some(after) = after {
+                plan.push(FileChange {
+                    path,
+                    before,
+                    after,
+                });
+            }
+        }
+        plan.push(FileChange {
+            after: to_json(&state_path, &state)?,
+            path: state_path,
+            before: state_before,
+        });
+        Ok((plan, expired))
+    }
+
+    /// Revert time-limited enables that have expired, returning them
+    pub fn revert_expired(&self) -> Result<Vec<ExpiringEnable>> {
+        let (plan, expired) = self.plan_revert_expired(Utc::now())?;
+        self.apply(&plan)?;
+        Ok(expired)
+    }
+
+    /// Time-limited enables that have not been reverted yet
+    pub fn load_expiring(&self) -> ExpiringEnables {
+        read_optional(&self.paths.expiring())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Approve or deny a project `.mcp.json` server in the current project's settings
+    pub fn set_mcp_approval(&self, name: &str, approved: bool, scope: Scope) -> Result<()> {
+        let plan = self.plan_set_mcp_approval(name, approved, scope)?;
+        self.apply(&plan)
+    }
+
+    /// Plan an MCP server approval without writing: the name is added to
+    /// `enabledMcpjsonServers` or `disabledMcpjsonServers` and removed from the other
+    pub fn plan_set_mcp_approval(
+        &self,
+        name: &str,
+        approved: bool,
+        scope: Scope,
+    ) -> Result<ChangePlan> {
+        let path = self.paths.settings_for(scope, None);
+        let before = read_optional(&path);
+
+        let mut settings = parse_settings(&path, before.as_deref())?;
+        let enabled = &mut settings.enabled_mcpjson_servers;
+        let disabled = &mut settings.disabled_mcpjson_servers;
+        let (add_to, remove_from) = if approved {
+            (enabled, disabled)
+        } else {
+            (disabled, enabled)
+        };
+        let added = !add_to.iter().any(|s| s == name);
+        if added {
+            add_to.push(name.to_string());
+        }
+        let removed = remove_from.iter().any(|s| s == name);
+        remove_from.retain(|s| s != name);
+
+        // Only rewrite the arrays that changed; everything else keeps its formatting
+        let (enabled_changed, disabled_changed) = if approved {
+            (added, removed)
+        } else {
+            (removed, added)
+        };
+        let mut edits = Vec::new();
+        if enabled_changed {
+            edits.push(("enabledMcpjsonServers", &settings.enabled_mcpjson_servers));
+        }
+        if disabled_changed {
+            edits.push(("disabledMcpjsonServers", &settings.disabled_mcpjson_servers));
+        }
+        let edited = before.as_deref().and_then(|source| {
+            edits
+                .iter()
+                .try_fold(source.to_string(), |source, (key, list)| {
+                    json_edit::set_top_level_member(&source, key, &Value::from(list.to_vec()))
+                })
+        });
+        let after = match edited {
+            Some(after) => after,
+            None => to_json(&path, &settings)? + "\n",
+        };
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            path,
+            before,
+            after,
+        });
+        Ok(plan)
+    }
+
     /// Toggle auto-update for a marketplace
     pub fn toggle_auto_update(&self, marketplace: &str) -> Result<bool> {
+        let new_state = !self.get_auto_update(marketplace)?;
+        let plan = self.plan_set_auto_update(marketplace, new_state)?;
+        self.apply(&plan)?;
+        Ok(new_state)
+    }
+
+    /// Plan setting a marketplace's auto-update flag without writing
+    pub fn plan_set_auto_update(&self, marketplace: &str, enabled: bool) -> Result<ChangePlan> {
         let path = self.paths.known_marketplaces();
-        let _lock = self.acquire_lock(&path)?;
+        let before = read_optional(&path);
 
         let mut marketplaces = self.load_known_marketplaces();
 
@@ -90,13 +344,15 @@ impl PluginService {
             .marketplaces
             .get_mut(marketplace)
             .ok_or_else(|| PluginError::MarketplaceNotFound(marketplace.to_string()))?;
-
-        entry.auto_update = !entry.auto_update;
-        let new_state = entry.auto_update;
-
-        self.write_json_atomic(&path, &marketplaces)?;
-
-        Ok(new_state)
+        entry.auto_update = Some(enabled);
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&path, &marketplaces)?,
+            path,
+            before,
+        });
+        Ok(plan)
     }
 
     /// Get auto-update status for a marketplace
@@ -105,35 +361,839 @@ impl PluginService {
         marketplaces
             .marketplaces
             .get(marketplace)
-            .map(|e| e.auto_update)
+            .map(|e| e.auto_update_enabled())
             .ok_or_else(|| PluginError::MarketplaceNotFound(marketplace.to_string()))
     }
 
-    fn set_plugin_enabled(&self, id: &str, scope: Scope, enabled: bool) -> Result<()> {
-        let path = match scope {
-            Scope::User => self.paths.user_settings(),
-            Scope::Project => self.paths.project_settings(),
-            Scope::Local => self.paths.local_settings(),
-        };
+    /// Register a marketplace: a local directory is used in place, a git URL or
+    /// GitHub `owner/repo` is cloned under the marketplaces directory. The name is
+    /// read from its catalog unless given. Returns the registered name.
+    pub fn add_marketplace(&self, source: &str, name: Option<&str>) -> Result<String> {
+        self.register_marketplace(parse_marketplace_source(source), name)
+    }
 
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|source| PluginError::ConfigWriteError {
-                path: parent.to_path_buf(),
+    /// Register a marketplace from an already parsed source, such as one declared
+    /// in `extraKnownMarketplaces`
+    pub fn register_marketplace(
+        &self,
+        source: MarketplaceSource,
+        name: Option<&str>,
+    ) -> Result<String> {
+        let planned = self.plan_register_marketplace(source, name)?;
+        self.apply_marketplace(&planned)?;
+        Ok(planned.name.clone())
+    }
+
+    /// Plan registering a marketplace. Git sources are cloned to a staging
+    /// directory to read their catalog; it is moved into place when the plan is
+    /// applied and deleted when the plan is dropped. A `ref` in the source selects
+    /// the branch or tag to clone.
+    pub fn plan_register_marketplace(
+        &self,
+        source: MarketplaceSource,
+        name: Option<&str>,
+    ) -> Result<MarketplacePlan> {
+        let (location, staging) = match &source {
+            MarketplaceSource::Directory { path, .. } => (path.clone(), None),
+            _ => {
+                let url = clone_url(&source)
+                    .ok_or_else(|| PluginError::UnsupportedMarketplaceSource(source.kind()))?;
+                let git_ref = match &source {
+                    MarketplaceSource::Github { other, .. }
+                    | MarketplaceSource::Git { other, .. } => {
+                        other.get("ref").and_then(|r| r.as_str())
+                    }
+                    _ => None,
+                };
+                let root = self.paths.marketplaces();
+                fs::create_dir_all(&root).map_err(|e| PluginError::ConfigWriteError {
+                    path: root.clone(),
+                    source: e,
+                })?;
+                let staging = root.join(format!(".clone-{}", std::process::id()));
+                let _ = fs::remove_dir_all(&staging);
+                if let Err(e) = clone_checkout(&url, git_ref, &staging) {
+                    let _ = fs::remove_dir_all(&staging);
+                    return Err(e);
+                }
+                (staging.clone(), Some(staging))
+            }
+        };
+        // Owns the staging clone from here on, so every early return discards it
+        let mut planned = MarketplacePlan {
+            name: String::new(),
+            entry: MarketplaceEntry {
                 source,
-            })?;
+                install_location: location,
+                last_updated: Utc::now().to_rfc3339(),
+                auto_update: None,
+                other: Default::default(),
+            },
+            plan: ChangePlan::new(),
+            staging,
+            removal: None,
+        };
+
+        let Some(catalog) = load_catalog(&planned.entry.install_location) else {
+            return Err(PluginError::NotAMarketplace(PathBuf::from(
+                planned.entry.source.location(),
+            )));
+        };
+        planned.name = name.map(str::to_string).unwrap_or(catalog.name);
+        if !is_kebab_case(&planned.name) {
+            return Err(PluginError::InvalidName(planned.name.clone()));
+        }
+
+        let path = self.paths.known_marketplaces();
+        let before = read_optional(&path);
+        let mut marketplaces = self.load_known_marketplaces();
+        if marketplaces.marketplaces.contains_key(&planned.name) {
+            return Err(PluginError::MarketplaceExists(planned.name.clone()));
+        }
+        if planned.staging.is_some() {
+            let target = self.paths.marketplaces().join(&planned.name);
+            if target.exists() {
+                return Err(PluginError::AlreadyExists(target));
+            }
+            planned.entry.install_location = target;
+        }
+
+        marketplaces
+            .marketplaces
+            .insert(planned.name.clone(), planned.entry.clone());
+        planned.plan.push(FileChange {
+            after: to_json(&path, &marketplaces)?,
+            path,
+            before,
+        });
+        Ok(planned)
+    }
+
+    /// Unregister a marketplace. Its checkout is deleted when it lives under the
+    /// marketplaces directory; a registered local directory is left alone.
+    pub fn remove_marketplace(&self, name: &str) -> Result<MarketplaceEntry> {
+        let planned = self.plan_remove_marketplace(name)?;
+        self.apply_marketplace(&planned)?;
+        Ok(planned.entry.clone())
+    }
+
+    /// Plan unregistering a marketplace without writing or deleting anything
+    pub fn plan_remove_marketplace(&self, name: &str) -> Result<MarketplacePlan> {
+        let path = self.paths.known_marketplaces();
+        let before = read_optional(&path);
+        let mut marketplaces = self.load_known_marketplaces();
+        let entry = marketplaces
+            .marketplaces
+            .remove(name)
+            .ok_or_else(|| PluginError::MarketplaceNotFound(name.to_string()))?;
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&path, &marketplaces)?,
+            path,
+            before,
+        });
+        let root = self.paths.marketplaces();
+        let removal = (entry.install_location.starts_with(&root)
+            && entry.install_location != root
+            && entry.install_location.exists())
+        .then(|| entry.install_location.clone());
+        Ok(MarketplacePlan {
+            name: name.to_string(),
+            entry,
+            plan,
+            staging: None,
+            removal,
+        })
+    }
+
+    /// Move a staged clone into place and write known_marketplaces.json, or write
+    /// it and delete the checkout of a removed marketplace
+    pub fn apply_marketplace(&self, planned: &MarketplacePlan) -> Result<()> {
+        let Some(staging) = &planned.staging else {
+            self.apply(&planned.plan)?;
+            if let Some(checkout) = &planned.removal {
+                let _ = fs::remove_dir_all(checkout);
+            }
+            return Ok(());
+        };
+
+        let target = &planned.entry.install_location;
+        if target.exists() {
+            return Err(PluginError::AlreadyExists(target.clone()));
+        }
+        fs::rename(staging, target).map_err(|e| PluginError::ConfigWriteError {
+            path: target.clone(),
+            source: e,
+        })?;
+        if let Err(e) = self.apply(&planned.plan) {
+            let _ = fs::remove_dir_all(target);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Plan registering a local plugin directory for development. The name is read
+    /// from its manifest; a directory that is already registered is a no-op.
+    pub fn plan_add_dev_plugin(&self, dir: &Path) -> Result<ChangePlan> {
+        let dir = dir
+            .canonicalize()
+            .map_err(|_| PluginError::NotFound(dir.display().to_string()))?;
+        if !dir.join(".claude-plugin").join("plugin.json").is_file() {
+            return Err(PluginError::NotAPlugin(dir));
+        }
+
+        let path = self.paths.dev_plugins();
+        let before = read_optional(&path);
+        let mut registry = self.load_dev_plugins();
+
+        let mut plan = ChangePlan::new();
+        if registry.dev_plugins.iter().any(|e| e.path == dir) {
+            return Ok(plan);
+        }
+
+        // An unparsable manifest is still registered so `ccpm dev list` can report it
+        let name = PluginDiscovery::with_paths(self.paths.clone())
+            .load_plugin_manifest(&dir)
+            .map(|m| m.name);
+        registry.dev_plugins.push(DevPluginEntry {
+            path: dir,
+            added_at: Utc::now().to_rfc3339(),
+            name,
+            other: Default::default(),
+        });
+
+        plan.push(FileChange {
+            after: to_json(&path, &registry)?,
+            path,
+            before,
+        });
+        Ok(plan)
+    }
+
+    /// Plan unregistering a development plugin, given its directory or name
+    pub fn plan_remove_dev_plugin(&self, plugin: &str) -> Result<ChangePlan> {
+        let path = self.paths.dev_plugins();
+        let before = read_optional(&path);
+        let mut registry = self.load_dev_plugins();
+
+        let target = Path::new(plugin).canonicalize().ok();
+        let count = registry.dev_plugins.len();
+        registry.dev_plugins.retain(|e| {
+            Some(&e.path) != target.as_ref()
+                && e.path != Path::new(plugin)
+                && e.name.as_deref() != Some(plugin)
+        });
+        if registry.dev_plugins.len() == count {
+            return Err(PluginError::NotFound(plugin.to_string()));
+        }
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&path, &registry)?,
+            path,
+            before,
+        });
+        Ok(plan)
+    }
+
+    /// Plan the temporary `enabledPlugins` overrides for `ccpm run` in the current
+    /// project's local settings, which win over every other scope
+    pub fn plan_run_overrides(&self, with: &[String], without: &[String]) -> Result<ChangePlan> {
+        let path = self.paths.local_settings();
+        if let Some(marker) = self.load_run_markers().into_iter().find(|(_, m)| {
+            m.settings_path == path && marker_is_live(m) && m.pid != std::process::id()
+        }) {
+            return Err(PluginError::RunInProgress {
+                path,
+                pid: marker.1.pid,
+            });
+        }
+
+        let before = read_optional(&path);
+        let mut after = before.clone();
+        for (id, enabled) in with
+            .iter()
+            .map(|id| (id, true))
+            .chain(without.iter().map(|id| (id, false)))
+        {
+            after = Some(set_enabled_in(&path, after.as_deref(), id, enabled)?);
+        }
+
+        let mut plan = ChangePlan::new();
+        if let Some(after) = after {
+            plan.push(FileChange {
+                path,
+                before,
+                after,
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Apply run overrides. The recovery marker is written first, so a ccpm killed
+    /// before `RunSession::restore` still gets the original back on its next start.
+    pub fn start_run(&self, plan: &ChangePlan) -> Result<RunSession<'_>> {
+        let Some(change) = plan.changes.first() else {
+            return Ok(RunSession {
+                service: self,
+                marker: None,
+            });
+        };
+
+        let dir = self.paths.run_markers();
+        fs::create_dir_all(&dir).map_err(|source| PluginError::ConfigWriteError {
+            path: dir.clone(),
+            source,
+        })?;
+        let marker = RunMarker {
+            pid: std::process::id(),
+            started_at: Utc::now().to_rfc3339(),
+            settings_path: change.path.clone(),
+            original: change.before.clone(),
+        };
+        let path = marker_path(&dir, marker.pid);
+        self.write_atomic(&path, &to_json(&path, &marker)?)?;
+
+        if let Err(e) = self.apply(plan) {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+        Ok(RunSession {
+            service: self,
+            marker: Some((path, marker)),
+        })
+    }
+
+    /// Restore the settings of `ccpm run` sessions whose process is gone.
+    /// Returns the settings files that were put back.
+    pub fn recover_runs(&self) -> Result<Vec<PathBuf>> {
+        let mut restored = Vec::new();
+        for (path, marker) in self.load_run_markers() {
+            if marker_is_live(&marker) {
+                continue;
+            }
+            self.restore_run(&path, &marker)?;
+            restored.push(marker.settings_path);
+        }
+        Ok(restored)
+    }
+
+    /// Settings files `recover_runs` would restore
+    pub fn interrupted_runs(&self) -> Vec<PathBuf> {
+        self.load_run_markers()
+            .into_iter()
+            .filter(|(_, marker)| !marker_is_live(marker))
+            .map(|(_, marker)| marker.settings_path)
+            .collect()
+    }
+
+    fn load_run_markers(&self) -> Vec<(PathBuf, RunMarker)> {
+        let Ok(entries) = fs::read_dir(self.paths.run_markers()) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let marker = serde_json::from_str(&read_optional(&path)?).ok()?;
+                Some((path, marker))
+            })
+            .collect()
+    }
+
+    /// Put back the exact content from before the run (or remove a file the run
+    /// created), then drop the marker
+    fn restore_run(&self, marker_path: &Path, marker: &RunMarker) -> Result<()> {
+        self.restore_file(&marker.settings_path, marker.original.as_deref())?;
+        let _ = fs::remove_file(marker_path);
+        Ok(())
+    }
+
+    /// Write back a file's saved content, or remove it if it did not exist before
+    fn restore_file(&self, path: &Path, original: Option<&str>) -> Result<()> {
+        let _lock = self.acquire_lock(path)?;
+        self.write_original(path, original)
+    }
+
+    /// Write back a file's earlier content, or remove it if it did not exist.
+    /// The caller holds the lock.
+    fn write_original(&self, path: &Path, original: Option<&str>) -> Result<()> {
+        match original {
+            Some(original) => self.write_atomic(path, original),
+            None => match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(PluginError::ConfigWriteError {
+                        path: path.to_path_buf(),
+                        source: e,
+                    })
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Start bisecting `plugin_ids` (the plugins active in this project). The state,
+    /// with the original local settings, is saved along with the first step.
+    pub fn start_bisect(&self, plugin_ids: Vec<String>) -> Result<BisectState> {
+        let planned = self.plan_start_bisect(plugin_ids)?;
+        self.apply_bisect(&planned)?;
+        Ok(planned.state)
+    }
+
+    /// Plan the start of a bisect without writing
+    pub fn plan_start_bisect(&self, plugin_ids: Vec<String>) -> Result<BisectPlan> {
+        if let Some(state) = self.load_bisect() {
+            return Err(PluginError::BisectInProgress(state.settings_path));
+        }
+        let path = self.paths.local_settings();
+        let state = BisectState::new(
+            path.clone(),
+            read_optional(&path),
+            plugin_ids,
+            Utc::now().to_rfc3339(),
+        );
+        self.plan_bisect_step(state)
+    }
+
+    /// Record whether the problem occurred in the current step. Writes the next
+    /// step, or restores the original settings once the bisect is done.
+    pub fn mark_bisect(&self, verdict: BisectVerdict) -> Result<BisectState> {
+        let planned = self.plan_mark_bisect(verdict)?;
+        self.apply_bisect(&planned)?;
+        Ok(planned.state)
+    }
+
+    /// Plan recording a step's result without writing
+    pub fn plan_mark_bisect(&self, verdict: BisectVerdict) -> Result<BisectPlan> {
+        let mut state = self.load_bisect().ok_or(PluginError::NoBisect)?;
+        state.record(verdict);
+        if state.is_done() {
+            Ok(self.plan_finish_bisect(state))
+        } else {
+            self.plan_bisect_step(state)
+        }
+    }
+
+    /// Abandon the bisect and restore the original local settings
+    pub fn reset_bisect(&self) -> Result<BisectState> {
+        let planned = self.plan_reset_bisect()?;
+        self.apply_bisect(&planned)?;
+        Ok(planned.state)
+    }
+
+    /// Plan abandoning the bisect without writing
+    pub fn plan_reset_bisect(&self) -> Result<BisectPlan> {
+        let state = self.load_bisect().ok_or(PluginError::NoBisect)?;
+        Ok(self.plan_finish_bisect(state))
+    }
+
+    /// Write a planned bisect step, then delete the files it drops
+    pub fn apply_bisect(&self, planned: &BisectPlan) -> Result<()> {
+        self.apply(&planned.plan)?;
+        for path in &planned.removals {
+            self.restore_file(path, None)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_bisect(&self) -> Option<BisectState> {
+        read_optional(&self.paths.bisect()).and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// The saved state plus the local settings for its current step, built from
+    /// the original content so every step differs from it only in `enabledPlugins`
+    fn plan_bisect_step(&self, state: BisectState) -> Result<BisectPlan> {
+        let state_path = self.paths.bisect();
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            before: read_optional(&state_path),
+            after: to_json(&state_path, &state)?,
+            path: state_path,
+        });
+
+        let path = &state.settings_path;
+        let mut after = state.original.clone();
+        for (id, enabled) in state.overrides() {
+            after = Some(set_enabled_in(path, after.as_deref(), id, enabled)?);
+        }
+        if let Some(after) = after {
+            plan.push(FileChange {
+                path: path.clone(),
+                before: read_optional(path),
+                after,
+            });
+        }
+        Ok(BisectPlan {
+            state,
+            plan,
+            removals: Vec::new(),
+        })
+    }
+
+    /// Restore the original local settings and drop the saved state
+    fn plan_finish_bisect(&self, state: BisectState) -> BisectPlan {
+        let mut plan = ChangePlan::new();
+        let mut removals = Vec::new();
+        match &state.original {
+            Some(original) => plan.push(FileChange {
+                path: state.settings_path.clone(),
+                before: read_optional(&state.settings_path),
+                after: original.clone(),
+            }),
+            None if state.settings_path.exists() => removals.push(state.settings_path.clone()),
+            None => {}
+        }
+        removals.push(self.paths.bisect());
+        BisectPlan {
+            state,
+            plan,
+            removals,
+        }
+    }
+
+    /// Fetch a marketplace checkout from its git remote and record the time in
+    /// known_marketplaces.json. Returns the new HEAD; None for directory
+    /// marketplaces, which are used in place.
+    pub fn refresh_marketplace(&self, name: &str) -> Result<Option<String>> {
+        let path = self.paths.known_marketplaces();
+        let before = read_optional(&path);
+        let mut marketplaces = self.load_known_marketplaces();
+
+        let entry = marketplaces
+            .marketplaces
+            .get_mut(name)
+            .ok_or_else(|| PluginError::MarketplaceNotFound(name.to_string()))?;
+        let git_ref = match &entry.source {
+            MarketplaceSource::Github { other, .. } | MarketplaceSource::Git { other, .. } => other
+                .get("ref")
+                .and_then(|r| r.as_str())
+                .map(str::to_string),
+            _ => return Ok(None),
+        };
+        let head = fetch_checkout(&entry.install_location, git_ref.as_deref())?;
+        entry.last_updated = Utc::now().to_rfc3339();
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&path, &marketplaces)?,
+            path,
+            before,
+        });
+        self.apply(&plan)?;
+        Ok(Some(head))
+    }
+
+    /// Copy a plugin from its marketplace checkout into a new cache directory and
+    /// point its install entries at it. The previous cache directory is kept and its
+    /// entries recorded for rollback; the one kept before that is removed.
+    pub fn update_plugin(&self, update: &PluginUpdate) -> Result<PathBuf> {
+        let planned = self.plan_update(update)?;
+        self.apply_update(&planned)?;
+        Ok(planned.target)
+    }
+
+    /// Plan an update without copying or writing anything: the cache directory to
+    /// create and the changes to installed_plugins.json and previous_installs.json
+    pub fn plan_update(&self, update: &PluginUpdate) -> Result<UpdatePlan> {
+        if let (true, Some(pin)) = (update.is_held(), &update.pin) {
+            return Err(PluginError::Pinned(
+                update.plugin_id.clone(),
+                pin.to_string(),
+            ));
+        }
+        let source_dir = update
+            .source_dir
+            .clone()
+            .ok_or_else(|| PluginError::NotUpdatable(update.plugin_id.clone()))?;
+
+        let installed_path = self.paths.installed_plugins();
+        let installed_before = read_optional(&installed_path);
+        let mut installed = parse_installed_plugins(&installed_path, installed_before.as_deref())?;
+        let entries = installed
+            .plugins
+            .get_mut(&update.plugin_id)
+            .filter(|entries| !entries.is_empty())
+            .ok_or_else(|| PluginError::NotFound(update.plugin_id.clone()))?;
+
+        let (name, marketplace) = update
+            .plugin_id
+            .split_once('@')
+            .unwrap_or((&update.plugin_id, ""));
+        let version = update
+            .available_version
+            .clone()
+            .unwrap_or_else(|| update.installed_version.clone());
+        let dir_name = cache_dir_name(&version, update.available_commit.as_deref());
+        let target = self
+            .paths
+            .plugin_cache()
+            .join(marketplace)
+            .join(name)
+            .join(&dir_name);
+        let mut planned = UpdatePlan {
+            source_dir,
+            target,
+            plan: ChangePlan::new(),
+            stale_caches: Vec::new(),
+        };
+        if entries.iter().all(|e| e.install_path == planned.target) {
+            return Ok(planned);
+        }
+
+        let replaced = entries.clone();
+        let now = Utc::now().to_rfc3339();
+        for entry in entries.iter_mut() {
+            entry.install_path = planned.target.clone();
+            entry.version = version.clone();
+            entry.git_commit_sha = update.available_commit.clone();
+            entry.last_updated = now.clone();
+        }
+
+        let previous_path = self.paths.previous_installs();
+        let previous_before = read_optional(&previous_path);
+        let mut previous = self.load_previous_installs();
+        let superseded = previous
+            .previous
+            .insert(update.plugin_id.clone(), replaced.clone());
+
+        // Only one previous version is kept per plugin
+        for old in superseded.unwrap_or_default() {
+            let still_used = old.install_path == planned.target
+                || replaced.iter().any(|e| e.install_path == old.install_path);
+            if !still_used && old.install_path.starts_with(self.paths.plugin_cache()) {
+                planned.stale_caches.push(old.install_path);
+            }
         }
 
-        let _lock = self.acquire_lock(&path)?;
+        planned.plan.push(FileChange {
+            after: to_json(&installed_path, &installed)?,
+            path: installed_path,
+            before: installed_before,
+        });
+        planned.plan.push(FileChange {
+            after: to_json(&previous_path, &previous)?,
+            path: previous_path,
+            before: previous_before,
+        });
+        Ok(planned)
+    }
 
-        let mut settings = self.load_settings(&path);
-        settings.enabled_plugins.insert(id.to_string(), enabled);
+    /// Make the cache copy of a planned update, then apply its file changes
+    pub fn apply_update(&self, planned: &UpdatePlan) -> Result<()> {
+        if planned.is_current() {
+            return Ok(());
+        }
+        let target = &planned.target;
+        let dir_name = target.file_name().unwrap_or_default().to_string_lossy();
+
+        // A target that already exists (e.g. the copy a rollback went back to) is
+        // kept as it is: previous_installs.json may still point at it
+        let created = !target.exists();
+        if created {
+            // Copy beside the target first so a failed copy never leaves a partial cache
+            let staging = target.with_file_name(format!(".{}.tmp", dir_name));
+            let write_error = |path: &Path| {
+                let path = path.to_path_buf();
+                move |source| PluginError::ConfigWriteError { path, source }
+            };
+            let _ = fs::remove_dir_all(&staging);
+            copy_dir(&planned.source_dir, &staging).map_err(write_error(&staging))?;
+            fs::rename(&staging, target).map_err(write_error(target))?;
+        }
 
-        self.write_json_atomic(&path, &settings)?;
+        if let Err(e) = self.apply(&planned.plan) {
+            // apply wrote nothing or put back what it wrote, so the new copy can
+            // go unless a failed rollback left installed_plugins.json pointing at it
+            let installed_path = self.paths.installed_plugins();
+            let points_at_target = planned.plan.changes.iter().any(|change| {
+                change.path == installed_path
+                    && read_optional(&installed_path).as_deref() == Some(change.after.as_str())
+            });
+            if created && !points_at_target {
+                let _ = fs::remove_dir_all(target);
+            }
+            return Err(e);
+        }
 
+        for stale in &planned.stale_caches {
+            let _ = fs::remove_dir_all(stale);
+        }
         Ok(())
     }
 
+    /// Plan pinning a plugin: updates skip it unless they bring it to the pinned
+    /// version or commit. Without either, it is held at what is installed.
+    pub fn plan_pin(
+        &self,
+        id: &str,
+        version: Option<String>,
+        commit: Option<String>,
+    ) -> Result<ChangePlan> {
+        let installed_path = self.paths.installed_plugins();
+        let installed =
+            parse_installed_plugins(&installed_path, read_optional(&installed_path).as_deref())?;
+        let entry = installed
+            .plugins
+            .get(id)
+            .and_then(|entries| entries.first())
+            .ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+        let (version, commit) = if version.is_none() && commit.is_none() {
+            (Some(entry.version.clone()), entry.git_commit_sha.clone())
+        } else {
+            (version, commit)
+        };
+
+        let path = self.paths.pins();
+        let before = read_optional(&path);
+        let mut pins = self.load_pins();
+
+        // Pinning again to the same target leaves the file alone
+        let mut plan = ChangePlan::new();
+        if pins
+            .pins
+            .get(id)
+            .is_some_and(|pin| pin.version == version && pin.commit == commit)
+        {
+            return Ok(plan);
+        }
+        pins.pins.insert(
+            id.to_string(),
+            Pin {
+                version,
+                commit,
+                pinned_at: Utc::now().to_rfc3339(),
+            },
+        );
+
+        plan.push(FileChange {
+            after: to_json(&path, &pins)?,
+            path,
+            before,
+        });
+        Ok(plan)
+    }
+
+    /// Plan removing a plugin's pin
+    pub fn plan_unpin(&self, id: &str) -> Result<ChangePlan> {
+        let path = self.paths.pins();
+        let before = read_optional(&path);
+        let mut pins = self.load_pins();
+        if pins.pins.remove(id).is_none() {
+            return Err(PluginError::NotPinned(id.to_string()));
+        }
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&path, &pins)?,
+            path,
+            before,
+        });
+        Ok(plan)
+    }
+
+    /// Plan switching a plugin back to the install entries kept by its last update.
+    /// The entries it replaces are kept in turn, so a second rollback undoes the first.
+    pub fn plan_rollback(&self, id: &str) -> Result<ChangePlan> {
+        let previous_path = self.paths.previous_installs();
+        let previous_before = read_optional(&previous_path);
+        let mut previous = self.load_previous_installs();
+        let mut restored = previous
+            .previous
+            .remove(id)
+            .filter(|entries| !entries.is_empty())
+            .ok_or_else(|| PluginError::NoPreviousVersion(id.to_string()))?;
+        if let Some(missing) = restored.iter().find(|e| !e.install_path.is_dir()) {
+            return Err(PluginError::NotFound(
+                missing.install_path.display().to_string(),
+            ));
+        }
+
+        let installed_path = self.paths.installed_plugins();
+        let installed_before = read_optional(&installed_path);
+        let mut installed = parse_installed_plugins(&installed_path, installed_before.as_deref())?;
+        let current = installed
+            .plugins
+            .get_mut(id)
+            .ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+        let now = Utc::now().to_rfc3339();
+        for entry in &mut restored {
+            entry.last_updated = now.clone();
+        }
+        let replaced = std::mem::replace(current, restored);
+        previous.previous.insert(id.to_string(), replaced);
+
+        let mut plan = ChangePlan::new();
+        plan.push(FileChange {
+            after: to_json(&installed_path, &installed)?,
+            path: installed_path,
+            before: installed_before,
+        });
+        plan.push(FileChange {
+            after: to_json(&previous_path, &previous)?,
+            path: previous_path,
+            before: previous_before,
+        });
+        Ok(plan)
+    }
+
+    fn load_pins(&self) -> Pins {
+        PluginDiscovery::with_paths(self.paths.clone()).load_pins()
+    }
+
+    /// Install entries kept for rollback, by plugin id
+    pub fn load_previous_installs(&self) -> PreviousInstalls {
+        read_optional(&self.paths.previous_installs())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Execute a plan. Each file is locked, checked against the content the plan was
+    /// computed from, and written atomically. A file that changed since planning
+    /// aborts with `PlanOutdated` instead of overwriting someone else's edit.
+    pub fn apply(&self, plan: &ChangePlan) -> Result<()> {
+        // Lock and check every file before writing any, so an outdated plan leaves
+        // nothing half-applied. A file changed twice is locked once.
+        let mut locks: Vec<(&Path, LockFileGuard)> = Vec::new();
+        let mut current: Vec<Option<String>> = Vec::new(); // Expected content, per lock
+        for change in &plan.changes {
+            let index = match locks.iter().position(|(path, _)| *path == change.path) {
+                Some(index) => index,
+                None => {
+                    if let Some(parent) = change.path.parent() {
+                        fs::create_dir_all(parent).map_err(|source| {
+                            PluginError::ConfigWriteError {
+                                path: parent.to_path_buf(),
+                                source,
+                            }
+                        })?;
+                    }
+                    locks.push((&change.path, self.acquire_lock(&change.path)?));
+                    current.push(read_optional(&change.path));
+                    locks.len() - 1
+                }
+            };
+            if current[index] != change.before {
+                return Err(PluginError::PlanOutdated(change.path.clone()));
+            }
+            current[index] = Some(change.after.clone());
+        }
+
+        for (i, change) in plan.changes.iter().enumerate() {
+            if let Err(e) = self.write_atomic(&change.path, &change.after) {
+                // Put back what was already written, newest first
+                for done in plan.changes[..i].iter().rev() {
+                    let _ = self.write_original(&done.path, done.before.as_deref());
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
     fn load_settings(&self, path: &Path) -> Settings {
         if !path.exists() {
             return Settings::default();
@@ -157,6 +1217,10 @@ impl PluginService {
             .unwrap_or_default()
     }
 
+    fn load_dev_plugins(&self) -> DevPlugins {
+        PluginDiscovery::with_paths(self.paths.clone()).load_dev_plugins()
+    }
+
     fn acquire_lock(&self, path: &Path) -> Result<LockFileGuard> {
         let lock_path = path.with_extension("lock");
 
@@ -226,23 +1290,17 @@ impl PluginService {
         })
     }
 
-    fn write_json_atomic<T: serde::Serialize>(&self, path: &Path, data: &T) -> Result<()> {
+    fn write_atomic(&self, path: &Path, content: &str) -> Result<()> {
         let temp_path = path.with_extension("tmp");
 
         // Write to temp file
-        let json =
-            serde_json::to_string_pretty(data).map_err(|e| PluginError::ConfigParseError {
-                path: path.to_path_buf(),
-                source: e,
-            })?;
-
         let mut file =
             File::create(&temp_path).map_err(|source| PluginError::ConfigWriteError {
                 path: temp_path.clone(),
                 source,
             })?;
 
-        file.write_all(json.as_bytes())
+        file.write_all(content.as_bytes())
             .map_err(|source| PluginError::ConfigWriteError {
                 path: temp_path.clone(),
                 source,
@@ -264,6 +1322,175 @@ impl PluginService {
     }
 }
 
+/// A planned plugin update, from `PluginService::plan_update`
+#[derive(Debug, Clone)]
+pub struct UpdatePlan {
+    pub source_dir: PathBuf, // Plugin directory in the marketplace checkout
+    pub target: PathBuf,     // New cache directory
+    pub plan: ChangePlan,
+    stale_caches: Vec<PathBuf>, // Previous copies dropped once applied
+}
+
+impl UpdatePlan {
+    /// The install entries already point at the target; nothing to copy or write
+    pub fn is_current(&self) -> bool {
+        self.plan.is_empty()
+    }
+
+    /// Cache directories the update deletes
+    pub fn stale_caches(&self) -> &[PathBuf] {
+        &self.stale_caches
+    }
+}
+
+/// A planned marketplace registration or removal, from
+/// `PluginService::plan_register_marketplace` or `plan_remove_marketplace`
+#[derive(Debug)]
+pub struct MarketplacePlan {
+    pub name: String,
+    pub entry: MarketplaceEntry, // Entry added or removed
+    pub plan: ChangePlan,
+    staging: Option<PathBuf>, // Fresh clone, moved to the entry's location once applied
+    removal: Option<PathBuf>, // Checkout deleted once applied
+}
+
+impl MarketplacePlan {
+    /// Clone moved into the marketplaces directory when the plan is applied
+    pub fn staging(&self) -> Option<&Path> {
+        self.staging.as_deref()
+    }
+
+    /// Checkout deleted when the plan is applied
+    pub fn removal(&self) -> Option<&Path> {
+        self.removal.as_deref()
+    }
+}
+
+impl Drop for MarketplacePlan {
+    // A staged clone that was never applied is not left behind
+    fn drop(&mut self) {
+        if let Some(staging) = &self.staging {
+            let _ = fs::remove_dir_all(staging);
+        }
+    }
+}
+
+/// A planned bisect step, from the `plan_*_bisect` methods on `PluginService`
+#[derive(Debug, Clone)]
+pub struct BisectPlan {
+    pub state: BisectState, // State after the step
+    pub plan: ChangePlan,
+    removals: Vec<PathBuf>, // Files deleted once applied
+}
+
+impl BisectPlan {
+    /// Files the step deletes: the saved state when the bisect ends, and the
+    /// local settings if the bisect created them
+    pub fn removals(&self) -> &[PathBuf] {
+        &self.removals
+    }
+}
+
+/// Temporary overrides applied by `PluginService::start_run`. Restored by
+/// `restore`, or on drop if the caller bails out early.
+pub struct RunSession<'a> {
+    service: &'a PluginService,
+    marker: Option<(PathBuf, RunMarker)>,
+}
+
+impl RunSession<'_> {
+    pub fn restore(mut self) -> Result<()> {
+        match self.marker.take() {
+            Some((path, marker)) => self.service.restore_run(&path, &marker),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RunSession<'_> {
+    fn drop(&mut self) {
+        if let Some((path, marker)) = self.marker.take() {
+            let _ = self.service.restore_run(&path, &marker);
+        }
+    }
+}
+
+/// Set `enabledPlugins[id]` in a settings document, editing it in place so only
+/// the targeted key changes. Missing or unparseable files fall back to a fresh
+/// serialization.
+fn set_enabled_in(path: &Path, source: Option<&str>, id: &str, enabled: bool) -> Result<String> {
+    let edited = source.and_then(|source| {
+        json_edit::set_nested_member(source, "enabledPlugins", id, &Value::Bool(enabled))
+    });
+    match edited {
+        Some(after) => Ok(after),
+        None => {
+            let mut settings = parse_settings(path, source)?;
+            settings.enabled_plugins.insert(id.to_string(), enabled);
+            Ok(to_json(path, &settings)? + "\n")
+        }
+    }
+}
+
+/// Remove `enabledPlugins[id]` from a settings document, leaving the plugin with
+/// no explicit value in that file
+fn remove_enabled_in(path: &Path, source: &str, id: &str) -> Result<String> {
+    if let Some(after) = json_edit::remove_nested_member(source, "enabledPlugins", id) {
+        return Ok(after);
+    }
+    let mut settings = parse_settings(path, Some(source))?;
+    settings.enabled_plugins.remove(id);
+    Ok(to_json(path, &settings)? + "\n")
+}
+
+/// Explicit `enabledPlugins[id]` value in a settings document
+fn enabled_value(source: Option<&str>, id: &str) -> Option<bool> {
+    serde_json::from_str::<Settings>(source?)
+        .ok()?
+        .enabled_plugins
+        .get(id)
+        .copied()
+}
+
+/// Parse a settings document; a file that does not exist yet reads as empty, but
+/// one that exists and is invalid is an error rather than something to rebuild
+fn parse_settings(path: &Path, content: Option<&str>) -> Result<Settings> {
+    match content {
+        Some(content) => {
+            serde_json::from_str(content).map_err(|source| PluginError::ConfigParseError {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+        None => Ok(Settings::default()),
+    }
+}
+
+/// Parse installed_plugins.json content; None reads as no installs
+fn parse_installed_plugins(path: &Path, content: Option<&str>) -> Result<InstalledPlugins> {
+    match content {
+        Some(content) => {
+            serde_json::from_str(content).map_err(|source| PluginError::ConfigParseError {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+        None => Ok(InstalledPlugins::default()),
+    }
+}
+
+/// Read a file's raw content, or None if it does not exist or cannot be read
+fn read_optional(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+fn to_json<T: serde::Serialize>(path: &Path, data: &T) -> Result<String> {
+    serde_json::to_string_pretty(data).map_err(|e| PluginError::ConfigParseError {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +1540,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_plan_does_not_write() {
+        let (_temp, service) = setup_test_env();
+
+        let plan = service
+            .plan_set_enabled("test@marketplace", Scope::Local, None, true)
+            .unwrap();
+
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].path, service.paths.local_settings());
+        assert!(plan.changes[0].before.is_none());
+        assert!(!service.paths.local_settings().exists());
+        assert!(!service
+            .paths
+            .local_settings()
+            .with_extension("lock")
+            .exists());
+
+        service.apply(&plan).unwrap();
+        assert!(service.paths.local_settings().exists());
+    }
+
+    #[test]
+    fn test_plan_in_other_project() {
+        let (temp, service) = setup_test_env();
+        let other = temp.path().join("other-project");
+
+        let plan = service
+            .plan_set_enabled("test@marketplace", Scope::Project, Some(&other), false)
+            .unwrap();
+        service.apply(&plan).unwrap();
+
+        let (project, _) = ConfigPaths::load_settings_from_project(&other);
+        assert_eq!(
+            project.unwrap().enabled_plugins.get("test@marketplace"),
+            Some(&false)
+        );
+        assert!(!service.paths.project_settings().exists());
+    }
+
+    #[test]
+    fn test_apply_rejects_outdated_plan() {
+        let (_temp, service) = setup_test_env();
+
+        let plan = service
+            .plan_set_enabled("test@marketplace", Scope::User, None, true)
+            .unwrap();
+
+        // Someone else writes the file between planning and applying
+        fs::write(service.paths.user_settings(), "{}").unwrap();
+
+        match service.apply(&plan) {
+            Err(PluginError::PlanOutdated(path)) => {
+                assert_eq!(path, service.paths.user_settings())
+            }
+            other => panic!("Expected PlanOutdated, got {:?}", other),
+        }
+        assert_eq!(
+            fs::read_to_string(service.paths.user_settings()).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn test_apply_checks_every_file_before_writing() {
+        let (_temp, service) = setup_test_env();
+
+        let mut plan = service
+            .plan_set_enabled("test@marketplace", Scope::User, None, true)
+            .unwrap();
+        plan.extend(
+            service
+                .plan_set_enabled("test@marketplace", Scope::Local, None, true)
+                .unwrap(),
+        );
+        assert_eq!(plan.changes.len(), 2);
+
+        // The second file changes after planning: the first must not be written
+        fs::write(service.paths.local_settings(), "{}").unwrap();
+        assert!(matches!(
+            service.apply(&plan),
+            Err(PluginError::PlanOutdated(path)) if path == service.paths.local_settings()
+        ));
+        assert!(!service.paths.user_settings().exists());
+    }
+
+    #[test]
+    fn test_apply_rolls_back_on_write_failure() {
+        let (_temp, service) = setup_test_env();
+        fs::write(service.paths.user_settings(), "{}").unwrap();
+
+        let mut plan = service
+            .plan_set_enabled("test@marketplace", Scope::User, None, true)
+            .unwrap();
+        plan.extend(
+            service
+                .plan_set_enabled("test@marketplace", Scope::Local, None, true)
+                .unwrap(),
+        );
+
+        // A directory where the second file should go makes its write fail
+        fs::create_dir_all(service.paths.local_settings().join("blocker")).unwrap();
+        assert!(service.apply(&plan).is_err());
+        assert_eq!(
+            fs::read_to_string(service.paths.user_settings()).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn test_plan_unchanged_setting_is_empty() {
+        let (_temp, service) = setup_test_env();
+
+        service
+            .enable_plugin("test@marketplace", Scope::User)
+            .unwrap();
+        let plan = service
+            .plan_set_enabled("test@marketplace", Scope::User, None, true)
+            .unwrap();
+
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_enable_preserves_settings_formatting() {
+        let (_temp, service) = setup_test_env();
+
+        let original = "{\n    \"permissions\": {\"allow\": [\"Bash(ls)\"]},\n    \"enabledPlugins\": {\n        \"zeta@m\": true,\n        \"alpha@m\": false\n    },\n    \"model\": \"opus\"\n}\n";
+        fs::write(service.paths.project_settings(), original).unwrap();
+
+        service.enable_plugin("alpha@m", Scope::Project).unwrap();
+
+        let written = fs::read_to_string(service.paths.project_settings()).unwrap();
+        assert_eq!(
+            written,
+            original.replace("\"alpha@m\": false", "\"alpha@m\": true")
+        );
+
+        // A single toggle is a single-line diff
+        let plan = service
+            .plan_set_enabled("zeta@m", Scope::Project, None, false)
+            .unwrap();
+        let diff = plan.unified_diff();
+        let changed: Vec<_> = diff
+            .lines()
+            .filter(|l| {
+                (l.starts_with('+') || l.starts_with('-'))
+                    && !l.starts_with("+++")
+                    && !l.starts_with("---")
+            })
+            .collect();
+        assert_eq!(
+            changed,
+            vec!["-        \"zeta@m\": true,", "+        \"zeta@m\": false,"]
+        );
+    }
+
+    #[test]
+    fn test_mcp_approval_moves_between_lists() {
+        let (_temp, service) = setup_test_env();
+
+        let original =
+            "{\n  \"model\": \"opus\",\n  \"disabledMcpjsonServers\": [\"github\", \"docs\"]\n}\n";
+        fs::write(service.paths.local_settings(), original).unwrap();
+
+        service
+            .set_mcp_approval("github", true, Scope::Local)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(service.paths.local_settings()).unwrap(),
+            "{\n  \"model\": \"opus\",\n  \"disabledMcpjsonServers\": [\"docs\"],\n  \"enabledMcpjsonServers\": [\"github\"]\n}\n"
+        );
+
+        service
+            .set_mcp_approval("github", false, Scope::Local)
+            .unwrap();
+        let settings = service.load_settings(&service.paths.local_settings());
+        assert!(settings.enabled_mcpjson_servers.is_empty());
+        assert_eq!(settings.disabled_mcpjson_servers, vec!["docs", "github"]);
+
+        // Already denied: nothing to write
+        let plan = service
+            .plan_set_mcp_approval("github", false, Scope::Local)
+            .unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_settings_are_left_untouched() {
+        let (_temp, service) = setup_test_env();
+        let path = service.paths.user_settings();
+        let malformed = "{\n  \"enabledPlugins\": {\"a@m\": true,\n";
+        fs::write(&path, malformed).unwrap();
+
+        for result in [
+            service.plan_set_enabled("b@m", Scope::User, None, true),
+            service.plan_set_mcp_approval("github", true, Scope::User),
+        ] {
+            assert!(matches!(
+                result,
+                Err(PluginError::ConfigParseError { path: ref p, .. }) if *p == path
+            ));
+        }
+        assert!(service.enable_plugin("b@m", Scope::User).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), malformed);
+    }
+
+    #[test]
+    fn test_mcp_approval_creates_settings_file() {
+        let (_temp, service) = setup_test_env();
+
+        service
+            .set_mcp_approval("github", true, Scope::Local)
+            .unwrap();
+        let settings = service.load_settings(&service.paths.local_settings());
+        assert_eq!(settings.enabled_mcpjson_servers, vec!["github"]);
+        assert!(!fs::read_to_string(service.paths.local_settings())
+            .unwrap()
+            .contains("disabledMcpjsonServers"));
+    }
+
+    #[test]
+    fn test_enable_new_settings_file_ends_with_newline() {
+        let (_temp, service) = setup_test_env();
+
+        service
+            .enable_plugin("test@marketplace", Scope::Local)
+            .unwrap();
+
+        let written = fs::read_to_string(service.paths.local_settings()).unwrap();
+        assert!(written.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_toggle_auto_update_keeps_unknown_fields() {
+        let (_temp, service) = setup_test_env();
+
+        let path = service.paths.known_marketplaces();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            include_str!("../../tests/fixtures/known_marketplaces.json"),
+        )
+        .unwrap();
+
+        assert!(service.toggle_auto_update("claude-code-workflows").unwrap());
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let entry = &written["claude-code-workflows"];
+        assert_eq!(entry["autoUpdate"], Value::Bool(true));
+        assert_eq!(entry["source"]["ref"], "main");
+        assert_eq!(
+            entry["lastSyncedCommit"],
+            "4c1d9e0b7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d"
+        );
+    }
+
     #[test]
     fn test_lock_file_contains_pid_and_timestamp() {
         let (_temp, service) = setup_test_env();
@@ -454,4 +1939,525 @@ mod tests {
 
         drop(guard);
     }
+
+    #[test]
+    fn test_add_and_remove_dev_plugin() {
+        let (temp, service) = setup_test_env();
+        let dir = temp.path().join("my-plugin");
+        fs::create_dir_all(dir.join(".claude-plugin")).unwrap();
+        fs::write(
+            dir.join(".claude-plugin").join("plugin.json"),
+            r#"{"name": "my-plugin"}"#,
+        )
+        .unwrap();
+
+        let plan = service.plan_add_dev_plugin(&dir).unwrap();
+        assert_eq!(plan.changes[0].path, service.paths.dev_plugins());
+        service.apply(&plan).unwrap();
+
+        let registry = service.load_dev_plugins();
+        assert_eq!(registry.dev_plugins.len(), 1);
+        assert_eq!(registry.dev_plugins[0].name.as_deref(), Some("my-plugin"));
+        assert_eq!(registry.dev_plugins[0].path, dir.canonicalize().unwrap());
+
+        // Adding again is a no-op
+        assert!(service.plan_add_dev_plugin(&dir).unwrap().is_empty());
+
+        let plan = service.plan_remove_dev_plugin("my-plugin").unwrap();
+        service.apply(&plan).unwrap();
+        assert!(service.load_dev_plugins().dev_plugins.is_empty());
+
+        assert!(matches!(
+            service.plan_remove_dev_plugin("my-plugin"),
+            Err(PluginError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_dev_plugin_requires_manifest() {
+        let (temp, service) = setup_test_env();
+        assert!(matches!(
+            service.plan_add_dev_plugin(temp.path()),
+            Err(PluginError::NotAPlugin(_))
+        ));
+        assert!(matches!(
+            service.plan_add_dev_plugin(&temp.path().join("missing")),
+            Err(PluginError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_overrides_are_restored_exactly() {
+        let (_temp, service) = setup_test_env();
+        let original = "{\n    \"model\":\"opus\",\n    \"enabledPlugins\": { \"a@m\": true }\n}";
+        fs::write(service.paths.local_settings(), original).unwrap();
+
+        let plan = service
+            .plan_run_overrides(&["b@m".to_string()], &["a@m".to_string()])
+            .unwrap();
+        let session = service.start_run(&plan).unwrap();
+        let settings = service.load_settings(&service.paths.local_settings());
+        assert_eq!(settings.enabled_plugins.get("a@m"), Some(&false));
+        assert_eq!(settings.enabled_plugins.get("b@m"), Some(&true));
+        assert_eq!(service.load_run_markers().len(), 1);
+
+        session.restore().unwrap();
+        assert_eq!(
+            fs::read_to_string(service.paths.local_settings()).unwrap(),
+            original
+        );
+        assert!(service.load_run_markers().is_empty());
+    }
+
+    #[test]
+    fn test_run_session_restores_on_drop() {
+        let (_temp, service) = setup_test_env();
+        let plan = service
+            .plan_run_overrides(&["b@m".to_string()], &[])
+            .unwrap();
+        drop(service.start_run(&plan).unwrap());
+        assert!(!service.paths.local_settings().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_recover_runs_from_dead_process() {
+        let (_temp, service) = setup_test_env();
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+
+        fs::write(
+            service.paths.local_settings(),
+            r#"{"enabledPlugins":{"b@m":true}}"#,
+        )
+        .unwrap();
+        let dir = service.paths.run_markers();
+        fs::create_dir_all(&dir).unwrap();
+        let marker = RunMarker {
+            pid: dead_pid,
+            started_at: Utc::now().to_rfc3339(),
+            settings_path: service.paths.local_settings(),
+            original: Some("{}\n".to_string()),
+        };
+        fs::write(
+            marker_path(&dir, dead_pid),
+            serde_json::to_string(&marker).unwrap(),
+        )
+        .unwrap();
+
+        let restored = service.recover_runs().unwrap();
+        assert_eq!(restored, vec![service.paths.local_settings()]);
+        assert_eq!(
+            fs::read_to_string(service.paths.local_settings()).unwrap(),
+            "{}\n"
+        );
+        assert!(service.load_run_markers().is_empty());
+    }
+
+    #[test]
+    fn test_enable_until_reverts_to_previous_value() {
+        let (_temp, service) = setup_test_env();
+        let original = "{\n  \"enabledPlugins\": {\n    \"a@m\": false\n  }\n}\n";
+        fs::write(service.paths.user_settings(), original).unwrap();
+        let now = Utc::now();
+
+        let plan = service
+            .plan_enable_until("a@m", Scope::User, None, now + chrono::Duration::hours(2))
+            .unwrap();
+        service.apply(&plan).unwrap();
+        let plan = service
+            .plan_enable_until("b@m", Scope::User, None, now + chrono::Duration::hours(1))
+            .unwrap();
+        service.apply(&plan).unwrap();
+        assert_eq!(service.load_expiring().expiring.len(), 2);
+
+        // Only b@m has expired after 90 minutes; it was unset before, so it is removed
+        let (plan, expired) = service
+            .plan_revert_expired(now + chrono::Duration::minutes(90))
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        service.apply(&plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(service.paths.user_settings()).unwrap(),
+            "{\n  \"enabledPlugins\": {\n    \"a@m\": true\n  }\n}\n"
+        );
+
+        let (plan, expired) = service
+            .plan_revert_expired(now + chrono::Duration::hours(3))
+            .unwrap();
+        assert_eq!(expired[0].plugin_id, "a@m");
+        service.apply(&plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(service.paths.user_settings()).unwrap(),
+            original
+        );
+        assert!(service.load_expiring().expiring.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_change_cancels_expiring_enable() {
+        let (_temp, service) = setup_test_env();
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let plan = service
+            .plan_enable_until("a@m", Scope::User, None, expires_at)
+            .unwrap();
+        service.apply(&plan).unwrap();
+
+        let plan = service
+            .plan_set_enabled("a@m", Scope::User, None, false)
+            .unwrap();
+        assert_eq!(plan.changes.len(), 2);
+        service.apply(&plan).unwrap();
+        assert!(service.load_expiring().expiring.is_empty());
+
+        // Nothing pending: no changes at all, not even to the state file
+        let (plan, expired) = service
+            .plan_revert_expired(expires_at + chrono::Duration::hours(1))
+            .unwrap();
+        assert!(plan.is_empty() && expired.is_empty());
+    }
+
+    #[test]
+    fn test_bisect_writes_steps_and_restores() {
+        let (_temp, service) = setup_test_env();
+        let original = "{\n  \"model\": \"opus\"\n}\n";
+        fs::write(service.paths.local_settings(), original).unwrap();
+        let ids: Vec<String> = ["a@m", "b@m", "c@m", "d@m"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let state = service.start_bisect(ids.clone()).unwrap();
+        assert_eq!(state.testing(), &ids[..2]);
+        let settings = service.load_settings(&service.paths.local_settings());
+        assert_eq!(settings.enabled_plugins.get("a@m"), Some(&true));
+        assert_eq!(settings.enabled_plugins.get("c@m"), Some(&false));
+        assert!(matches!(
+            service.start_bisect(ids),
+            Err(PluginError::BisectInProgress(_))
+        ));
+
+        // c@m is the culprit
+        let state = service.mark_bisect(BisectVerdict::Good).unwrap();
+        assert_eq!(state.testing(), &["c@m".to_string()]);
+        let state = service.mark_bisect(BisectVerdict::Bad).unwrap();
+        assert_eq!(state.culprit(), Some("c@m"));
+
+        assert_eq!(
+            fs::read_to_string(service.paths.local_settings()).unwrap(),
+            original
+        );
+        assert!(service.load_bisect().is_none());
+        assert!(matches!(
+            service.mark_bisect(BisectVerdict::Bad),
+            Err(PluginError::NoBisect)
+        ));
+    }
+
+    #[test]
+    fn test_update_plugin_keeps_previous_cache() {
+        let (temp, service) = setup_test_env();
+        let checkout = temp.path().join("market");
+        let write_plugin = |version: &str| {
+            fs::create_dir_all(checkout.join("demo/.claude-plugin")).unwrap();
+            fs::write(
+                checkout.join("demo/.claude-plugin/plugin.json"),
+                format!(r#"{{"name": "demo", "version": "{}"}}"#, version),
+            )
+            .unwrap();
+        };
+        let old_cache = service.paths.plugin_cache().join("market/demo/1.0.0");
+        fs::create_dir_all(&old_cache).unwrap();
+        fs::write(
+            service.paths.installed_plugins(),
+            serde_json::json!({
+                "version": 2,
+                "plugins": {"demo@market": [{
+                    "scope": "user",
+                    "installPath": old_cache,
+                    "version": "1.0.0",
+                    "installedAt": "2026-01-01T00:00:00Z",
+                    "lastUpdated": "2026-01-01T00:00:00Z"
+                }]}
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let update_to = |version: &str| {
+            let mut update = PluginUpdate::new(
+                "demo@market".to_string(),
+                String::new(),
+                None,
+                Some(version.to_string()),
+                Some(format!("{:0<40}", version.replace('.', ""))),
+            );
+            update.source_dir = Some(checkout.join("demo"));
+            service.update_plugin(&update).unwrap()
+        };
+
+        write_plugin("1.1.0");
+        let first = update_to("1.1.0");
+        assert_eq!(first.file_name().unwrap(), "1.1.0-1100000");
+        assert!(first.join(".claude-plugin/plugin.json").is_file());
+        assert!(old_cache.is_dir());
+        let installed: InstalledPlugins =
+            serde_json::from_str(&fs::read_to_string(service.paths.installed_plugins()).unwrap())
+                .unwrap();
+        let entry = &installed.plugins["demo@market"][0];
+        assert_eq!(entry.install_path, first);
+        assert_eq!(entry.version, "1.1.0");
+        assert_eq!(entry.installed_at, "2026-01-01T00:00:00Z");
+        let previous = service.load_previous_installs();
+        assert_eq!(previous.previous["demo@market"][0].install_path, old_cache);
+
+        // A second update keeps only the copy it replaces
+        write_plugin("1.2.0");
+        update_to("1.2.0");
+        assert!(!old_cache.exists());
+        assert!(first.is_dir());
+        let previous = service.load_previous_installs();
+        assert_eq!(previous.previous["demo@market"][0].install_path, first);
+
+        // No source directory: listed with a source outside the marketplace repository
+        let unlisted = PluginUpdate::new("x@market".into(), "1.0.0".into(), None, None, None);
+        assert!(matches!(
+            service.update_plugin(&unlisted),
+            Err(PluginError::NotUpdatable(_))
+        ));
+    }
+
+    #[test]
+    fn test_failed_update_keeps_install_intact() {
+        let (temp, service) = setup_test_env();
+        let source = temp.path().join("market/demo");
+        fs::create_dir_all(source.join(".claude-plugin")).unwrap();
+        fs::write(
+            source.join(".claude-plugin/plugin.json"),
+            r#"{"name": "demo", "version": "1.1.0"}"#,
+        )
+        .unwrap();
+        let old_cache = service.paths.plugin_cache().join("market/demo/1.0.0");
+        fs::create_dir_all(&old_cache).unwrap();
+        let installed = serde_json::json!({
+            "version": 2,
+            "plugins": {"demo@market": [{
+                "scope": "user",
+                "installPath": old_cache,
+                "version": "1.0.0",
+                "installedAt": "2026-01-01T00:00:00Z",
+                "lastUpdated": "2026-01-01T00:00:00Z"
+            }]}
+        })
+        .to_string();
+        fs::write(service.paths.installed_plugins(), &installed).unwrap();
+
+        // previous_installs.json cannot be written, after installed_plugins.json was
+        fs::create_dir_all(service.paths.previous_installs().join("blocker")).unwrap();
+        let mut update = PluginUpdate::new(
+            "demo@market".to_string(),
+            "1.0.0".to_string(),
+            None,
+            Some("1.1.0".to_string()),
+            None,
+        );
+        update.source_dir = Some(source);
+        assert!(service.update_plugin(&update).is_err());
+
+        assert_eq!(
+            fs::read_to_string(service.paths.installed_plugins()).unwrap(),
+            installed
+        );
+        assert!(old_cache.is_dir());
+        assert!(!service
+            .paths
+            .plugin_cache()
+            .join("market/demo/1.1.0")
+            .exists());
+    }
+
+    #[test]
+    fn test_outdated_update_leaves_newer_files_and_existing_cache() {
+        let (temp, service) = setup_test_env();
+        let source = temp.path().join("market/demo");
+        fs::create_dir_all(source.join(".claude-plugin")).unwrap();
+        fs::write(
+            source.join(".claude-plugin/plugin.json"),
+            r#"{"name": "demo", "version": "1.1.0"}"#,
+        )
+        .unwrap();
+        let old_cache = service.paths.plugin_cache().join("market/demo/1.0.0");
+        fs::create_dir_all(&old_cache).unwrap();
+        fs::write(
+            service.paths.installed_plugins(),
+            serde_json::json!({
+                "version": 2,
+                "plugins": {"demo@market": [{
+                    "scope": "user",
+                    "installPath": old_cache,
+                    "version": "1.0.0",
+                    "installedAt": "2026-01-01T00:00:00Z",
+                    "lastUpdated": "2026-01-01T00:00:00Z"
+                }]}
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let mut update = PluginUpdate::new(
+            "demo@market".to_string(),
+            "1.0.0".to_string(),
+            None,
+            Some("1.1.0".to_string()),
+            None,
+        );
+        update.source_dir = Some(source);
+
+        // Someone else writes installed_plugins.json after the plan was made
+        let planned = service.plan_update(&update).unwrap();
+        let newer = r#"{"version": 2, "plugins": {}}"#;
+        fs::write(service.paths.installed_plugins(), newer).unwrap();
+        assert!(matches!(
+            service.apply_update(&planned),
+            Err(PluginError::PlanOutdated(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(service.paths.installed_plugins()).unwrap(),
+            newer
+        );
+        assert!(!planned.target.exists());
+
+        // A target that was already there is neither replaced nor deleted
+        fs::create_dir_all(&planned.target).unwrap();
+        fs::write(planned.target.join("kept"), "").unwrap();
+        assert!(service.apply_update(&planned).is_err());
+        assert!(planned.target.join("kept").exists());
+        assert!(old_cache.is_dir());
+    }
+
+    #[test]
+    fn test_rollback_swaps_with_previous_install() {
+        let (temp, service) = setup_test_env();
+        let old_cache = temp.path().join("cache/1.0.0");
+        let new_cache = temp.path().join("cache/1.1.0");
+        fs::create_dir_all(&old_cache).unwrap();
+        fs::create_dir_all(&new_cache).unwrap();
+        fs::create_dir_all(service.paths.installed_plugins().parent().unwrap()).unwrap();
+        let entries = |path: &Path, version: &str| {
+            serde_json::json!([{
+                "scope": "user",
+                "installPath": path,
+                "version": version,
+                "installedAt": "2026-01-01T00:00:00Z",
+                "lastUpdated": "2026-01-01T00:00:00Z"
+            }])
+        };
+        fs::write(
+            service.paths.installed_plugins(),
+            serde_json::json!({"version": 2, "plugins": {"demo@m": entries(&new_cache, "1.1.0")}})
+                .to_string(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            service.plan_rollback("demo@m"),
+            Err(PluginError::NoPreviousVersion(_))
+        ));
+        fs::create_dir_all(service.paths.ccpm_dir()).unwrap();
+        fs::write(
+            service.paths.previous_installs(),
+            serde_json::json!({"previous": {"demo@m": entries(&old_cache, "1.0.0")}}).to_string(),
+        )
+        .unwrap();
+
+        let installed_version = || {
+            let installed: InstalledPlugins = serde_json::from_str(
+                &fs::read_to_string(service.paths.installed_plugins()).unwrap(),
+            )
+            .unwrap();
+            installed.plugins["demo@m"][0].version.clone()
+        };
+        service
+            .apply(&service.plan_rollback("demo@m").unwrap())
+            .unwrap();
+        assert_eq!(installed_version(), "1.0.0");
+        assert_eq!(
+            service.load_previous_installs().previous["demo@m"][0].install_path,
+            new_cache
+        );
+
+        // Rolling back again undoes the rollback
+        service
+            .apply(&service.plan_rollback("demo@m").unwrap())
+            .unwrap();
+        assert_eq!(installed_version(), "1.1.0");
+
+        // Pinning without a target holds the installed version
+        service
+            .apply(&service.plan_pin("demo@m", None, None).unwrap())
+            .unwrap();
+        assert_eq!(
+            service.load_pins().pins["demo@m"].version.as_deref(),
+            Some("1.1.0")
+        );
+        assert!(service.plan_pin("demo@m", None, None).unwrap().is_empty());
+        service
+            .apply(&service.plan_unpin("demo@m").unwrap())
+            .unwrap();
+        assert!(matches!(
+            service.plan_unpin("demo@m"),
+            Err(PluginError::NotPinned(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_and_remove_directory_marketplace() {
+        let (temp, service) = setup_test_env();
+        let dir = temp.path().join("team-market");
+        fs::create_dir_all(dir.join(".claude-plugin")).unwrap();
+        fs::write(
+            dir.join(".claude-plugin/marketplace.json"),
+            r#"{"name": "team", "plugins": []}"#,
+        )
+        .unwrap();
+        let source = dir.to_string_lossy();
+
+        assert_eq!(service.add_marketplace(&source, None).unwrap(), "team");
+        let entry = &service.load_known_marketplaces().marketplaces["team"];
+        assert_eq!(entry.source.kind(), "directory");
+        assert_eq!(entry.install_location, dir.canonicalize().unwrap());
+        assert!(!service.get_auto_update("team").unwrap());
+
+        assert!(matches!(
+            service.add_marketplace(&source, None),
+            Err(PluginError::MarketplaceExists(_))
+        ));
+        assert_eq!(
+            service.add_marketplace(&source, Some("team-2")).unwrap(),
+            "team-2"
+        );
+        assert!(matches!(
+            service.add_marketplace(&temp.path().to_string_lossy(), None),
+            Err(PluginError::NotAMarketplace(_))
+        ));
+
+        // Planning writes nothing, and a directory outside the marketplaces root is kept
+        let planned = service.plan_remove_marketplace("team").unwrap();
+        assert_eq!(planned.removal(), None);
+        assert!(service
+            .load_known_marketplaces()
+            .marketplaces
+            .contains_key("team"));
+
+        service.remove_marketplace("team").unwrap();
+        assert!(!service
+            .load_known_marketplaces()
+            .marketplaces
+            .contains_key("team"));
+        // Registered in place, so the directory itself is kept
+        assert!(dir.join(".claude-plugin/marketplace.json").exists());
+        assert!(matches!(
+            service.remove_marketplace("team"),
+            Err(PluginError::MarketplaceNotFound(_))
+        ));
+    }
 }
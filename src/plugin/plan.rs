@@ -0,0 +1,203 @@
+use serde_json::{json, Value};
+use similar::TextDiff;
+use std::path::PathBuf;
+
+/// A single file rewrite computed by a plan step.
+/// `before` is the raw file content at planning time (None = file does not exist yet).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl FileChange {
+    pub fn is_noop(&self) -> bool {
+        self.before.as_deref() == Some(self.after.as_str())
+    }
+}
+
+/// The set of file changes a mutating command would make.
+/// Produced by the `plan_*` methods on `PluginService` and executed by `PluginService::apply`,
+/// so a dry-run preview and the real write always go through the same code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangePlan {
+    pub changes: Vec<FileChange>,
+}
+
+impl ChangePlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a change to the plan. No-op rewrites are dropped.
+    pub fn push(&mut self, change: FileChange) {
+        if !change.is_noop() {
+            self.changes.push(change);
+        }
+    }
+
+    /// Merge another plan into this one
+    pub fn extend(&mut self, other: ChangePlan) {
+        for change in other.changes {
+            self.push(change);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Unified diff of every change, one file section after another
+    pub fn unified_diff(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            let path = change.path.display().to_string();
+            let old_header = if change.before.is_some() {
+                format!("a/{}", path)
+            } else {
+                "/dev/null".to_string()
+            };
+            let new_header = format!("b/{}", path);
+            let before = change.before.as_deref().unwrap_or("");
+
+            let diff = TextDiff::from_lines(before, change.after.as_str());
+            let mut text = diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_header, &new_header)
+                .to_string();
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            out.push_str(&text);
+        }
+        out
+    }
+
+    /// JSON-patch (RFC 6902) view of the plan: `[{"file": ..., "patch": [ops]}]`.
+    /// Files whose content is not valid JSON are shown as a whole-document replace.
+    pub fn json_patch(&self) -> Value {
+        let files: Vec<Value> = self
+            .changes
+            .iter()
+            .map(|change| {
+                let before = change
+                    .before
+                    .as_deref()
+                    .and_then(|s| serde_json::from_str::<Value>(s).ok());
+                let after = serde_json::from_str::<Value>(&change.after)
+                    .unwrap_or_else(|_| Value::String(change.after.clone()));
+
+                let mut ops = Vec::new();
+                match before {
+                    Some(before) => json_diff(&before, &after, "", &mut ops),
+                    None => ops.push(json!({ "op": "add", "path": "", "value": after })),
+                }
+
+                json!({
+                    "file": change.path.display().to_string(),
+                    "patch": ops,
+                })
+            })
+            .collect();
+
+        Value::Array(files)
+    }
+}
+
+/// Recursive object diff producing RFC 6902 operations.
+/// Arrays and scalars are compared as a whole and replaced when different.
+fn json_diff(before: &Value, after: &Value, pointer: &str, ops: &mut Vec<Value>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let path = format!("{}/{}", pointer, escape_pointer(key));
+                match new.get(key) {
+                    Some(new_value) => json_diff(old_value, new_value, &path, ops),
+                    None => ops.push(json!({ "op": "remove", "path": path })),
+                }
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    let path = format!("{}/{}", pointer, escape_pointer(key));
+                    ops.push(json!({ "op": "add", "path": path, "value": new_value }));
+                }
+            }
+        }
+        _ if before != after => {
+            ops.push(json!({ "op": "replace", "path": pointer, "value": after }));
+        }
+        _ => {}
+    }
+}
+
+/// Escape a key for use as a JSON pointer reference token (RFC 6901)
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(before: Option<&str>, after: &str) -> FileChange {
+        FileChange {
+            path: PathBuf::from("settings.json"),
+            before: before.map(str::to_string),
+            after: after.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_push_drops_noop_changes() {
+        let mut plan = ChangePlan::new();
+        plan.push(change(Some("{}"), "{}"));
+        assert!(plan.is_empty());
+
+        plan.push(change(Some("{}"), "{\"a\": 1}"));
+        assert_eq!(plan.changes.len(), 1);
+    }
+
+    #[test]
+    fn test_unified_diff_shows_file_and_lines() {
+        let mut plan = ChangePlan::new();
+        plan.push(change(Some("{\n  \"a\": 1\n}"), "{\n  \"a\": 2\n}"));
+
+        let diff = plan.unified_diff();
+        assert!(diff.contains("--- a/settings.json"));
+        assert!(diff.contains("+++ b/settings.json"));
+        assert!(diff.contains("-  \"a\": 1"));
+        assert!(diff.contains("+  \"a\": 2"));
+    }
+
+    #[test]
+    fn test_unified_diff_new_file() {
+        let mut plan = ChangePlan::new();
+        plan.push(change(None, "{}\n"));
+
+        let diff = plan.unified_diff();
+        assert!(diff.contains("--- /dev/null"));
+        assert!(diff.contains("+{}"));
+    }
+
+    #[test]
+    fn test_json_patch_ops() {
+        let mut plan = ChangePlan::new();
+        plan.push(change(
+            Some(r#"{"enabledPlugins": {"a@m": true, "b@m": true}, "x": 1}"#),
+            r#"{"enabledPlugins": {"a@m": false, "c/d@m": true}, "x": 1}"#,
+        ));
+
+        let patch = plan.json_patch();
+        let ops = patch[0]["patch"].as_array().unwrap();
+        assert!(
+            ops.contains(&json!({"op": "replace", "path": "/enabledPlugins/a@m", "value": false}))
+        );
+        assert!(ops.contains(&json!({"op": "remove", "path": "/enabledPlugins/b@m"})));
+        assert!(
+            ops.contains(&json!({"op": "add", "path": "/enabledPlugins/c~1d@m", "value": true}))
+        );
+        assert_eq!(ops.len(), 3);
+    }
+}
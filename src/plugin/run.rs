@@ -0,0 +1,107 @@
+//! Launching Claude with temporary plugin overrides (`ccpm run`).
+//!
+//! Overrides are written to the project's local settings, which take precedence
+//! over every other scope, and the previous content is put back when the child
+//! exits. Before anything is written, a marker holding the original content is
+//! saved under `~/.config/ccpm/runs/`. If ccpm dies mid-run (SIGKILL, a closed
+//! terminal), the marker is left behind and the next ccpm start restores the file.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+/// Recovery record for one `ccpm run`, named after the ccpm process id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMarker {
+    pub pid: u32,
+    pub started_at: String,
+    pub settings_path: PathBuf,
+    /// Settings content before the overrides; None if the file did not exist
+    pub original: Option<String>,
+}
+
+/// Run `command` (program first) with `--plugin-dir <dir>` inserted before its
+/// own arguments, and wait for it. Ctrl-C goes to the child; ccpm ignores it
+/// while waiting so it is still around to restore the settings afterwards.
+pub fn run_with_plugin_dirs(command: &[String], plugin_dirs: &[PathBuf]) -> io::Result<ExitStatus> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no command given"))?;
+
+    let mut child = Command::new(program)
+        .args(plugin_dir_args(plugin_dirs))
+        .args(args)
+        .spawn()?;
+
+    // Installed after spawning: ignored signals would be inherited across exec
+    let _interrupts = IgnoreInterrupts::new();
+    child.wait()
+}
+
+fn plugin_dir_args(plugin_dirs: &[PathBuf]) -> Vec<&std::ffi::OsStr> {
+    plugin_dirs
+        .iter()
+        .flat_map(|dir| ["--plugin-dir".as_ref(), dir.as_os_str()])
+        .collect()
+}
+
+/// Whether a marker belongs to a ccpm that is still running
+pub(crate) fn marker_is_live(marker: &RunMarker) -> bool {
+    super::operations::is_process_running(marker.pid)
+}
+
+/// Marker file name for a ccpm process
+pub(crate) fn marker_path(dir: &Path, pid: u32) -> PathBuf {
+    dir.join(format!("{}.json", pid))
+}
+
+/// Ignores SIGINT, SIGQUIT and SIGHUP until dropped. The terminal delivers them
+/// to the whole foreground process group, so the child still receives them.
+struct IgnoreInterrupts {
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sighandler_t)>,
+}
+
+impl IgnoreInterrupts {
+    #[cfg(unix)]
+    fn new() -> Self {
+        let previous = [libc::SIGINT, libc::SIGQUIT, libc::SIGHUP]
+            .into_iter()
+            .map(|signal| (signal, unsafe { libc::signal(signal, libc::SIG_IGN) }))
+            .collect();
+        Self { previous }
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for IgnoreInterrupts {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        for &(signal, handler) in &self.previous {
+            unsafe {
+                libc::signal(signal, handler);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_dir_args() {
+        let dirs = vec![PathBuf::from("/a"), PathBuf::from("/b")];
+        let args: Vec<_> = plugin_dir_args(&dirs)
+            .into_iter()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args, vec!["--plugin-dir", "/a", "--plugin-dir", "/b"]);
+    }
+}
@@ -0,0 +1,433 @@
+//! Skeletons for new plugins and local marketplaces.
+//!
+//! Everything is returned as a `ChangePlan` of new files, so `--dry-run` can show
+//! the skeleton and `PluginService::apply` writes it.
+
+use super::{
+    config::{MarketplaceManifest, MarketplacePlugin, PluginAuthor, PluginManifest},
+    plan::{ChangePlan, FileChange},
+    validate::is_kebab_case,
+    PluginError, Result,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for `plan_new_plugin`
+#[derive(Debug, Clone, Default)]
+pub struct NewPlugin {
+    pub name: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Plan a plugin skeleton in `parent/<name>`: manifest, an example command, agent
+/// and hook, and a README. Fails if the directory already has files in it.
+pub fn plan_new_plugin(parent: &Path, options: &NewPlugin) -> Result<ChangePlan> {
+    let name = options.name.as_str();
+    if !is_kebab_case(name) {
+        return Err(PluginError::InvalidName(name.to_string()));
+    }
+    let root = parent.join(name);
+    if fs::read_dir(&root).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(PluginError::AlreadyExists(root));
+    }
+
+    let description = options
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("TODO: describe what {} does", name));
+    let author = options.author.clone().unwrap_or_else(|| "TODO".to_string());
+
+    let files = [
+        (
+            ".claude-plugin/plugin.json",
+            format!(
+                r#"{{
+  "name": {name},
+  "description": {description},
+  "version": "0.1.0",
+  "author": {{
+    "name": {author}
+  }},
+  "license": "MIT",
+  "keywords": []
+}}
+"#,
+                name = json_string(name),
+                description = json_string(&description),
+                author = json_string(&author),
+            ),
+        ),
+        (
+            "commands/hello.md",
+            format!(
+                r#"---
+description: Example command, invoked as /hello
+argument-hint: [name]
+---
+
+Greet $ARGUMENTS and list what the {name} plugin can do.
+"#
+            ),
+        ),
+        (
+            "agents/example-agent.md",
+            format!(
+                r#"---
+name: {name}-agent
+description: Example agent. Describe when Claude should delegate to it.
+tools: Read, Grep, Glob
+---
+
+You are a focused assistant provided by the {name} plugin.
+Replace this prompt with the agent's instructions.
+"#
+            ),
+        ),
+        (
+            "hooks/hooks.json",
+            r#"{
+  "hooks": {
+    "PostToolUse": [
+      {
+        "matcher": "Write|Edit",
+        "hooks": [
+          {
+            "type": "command",
+            "command": "sh ${CLAUDE_PLUGIN_ROOT}/scripts/after-edit.sh"
+          }
+        ]
+      }
+    ]
+  }
+}
+"#
+            .to_string(),
+        ),
+        (
+            "scripts/after-edit.sh",
+            r#"#!/bin/sh
+# Runs after Claude writes or edits a file; the hook input arrives as JSON on stdin.
+# Replace with a formatter or linter for the edited file.
+exit 0
+"#
+            .to_string(),
+        ),
+        (
+            "README.md",
+            format!(
+                r#"# {name}
+
+{description}
+
+## Contents
+
+| Path | What it is |
+|------|------------|
+| `.claude-plugin/plugin.json` | Manifest: name, version, author and component paths |
+| `commands/hello.md` | The `/hello` slash command |
+| `agents/example-agent.md` | The `{name}-agent` subagent |
+| `hooks/hooks.json` | Runs `scripts/after-edit.sh` after every Write or Edit |
+
+## Development
+
+Check the plugin before committing:
+
+```bash
+ccpm validate {name}
+```
+"#
+            ),
+        ),
+    ];
+
+    let mut plan = ChangePlan::new();
+    for (relative, content) in files {
+        plan.push(FileChange {
+            path: root.join(relative),
+            before: None,
+            after: content,
+        });
+    }
+    Ok(plan)
+}
+
+/// Plan a `.claude-plugin/marketplace.json` in `dir` listing the plugins found there.
+/// `name` defaults to the directory name; fails if the catalog already exists.
+pub fn plan_new_marketplace(
+    dir: &Path,
+    name: Option<&str>,
+    owner: Option<&str>,
+) -> Result<ChangePlan> {
+    let path = marketplace_manifest_path(dir);
+    if path.exists() {
+        return Err(PluginError::AlreadyExists(path));
+    }
+
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => dir
+            .canonicalize()
+            .ok()
+            .and_then(|d| d.file_name().map(|n| n.to_string_lossy().to_lowercase()))
+            .unwrap_or_default(),
+    };
+    if !is_kebab_case(&name) {
+        return Err(PluginError::InvalidName(name));
+    }
+
+    let mut manifest = MarketplaceManifest {
+        name,
+        owner: Some(PluginAuthor {
+            name: owner.unwrap_or("TODO").to_string(),
+            email: None,
+            url: None,
+        }),
+        plugins: Vec::new(),
+        other: BTreeMap::new(),
+    };
+    sync_plugins(&mut manifest, dir);
+
+    let mut plan = ChangePlan::new();
+    plan.push(FileChange {
+        after: to_json(&path, &manifest)?,
+        path,
+        before: None,
+    });
+    Ok(plan)
+}
+
+/// Plan refreshing the plugin list of an existing marketplace catalog: plugins found
+/// in `dir` are added or updated, local entries whose directory is gone are dropped.
+/// Remote entries and extra fields are left alone.
+pub fn plan_build_marketplace(dir: &Path) -> Result<ChangePlan> {
+    let path = marketplace_manifest_path(dir);
+    let before = fs::read_to_string(&path).map_err(|source| PluginError::ConfigReadError {
+        path: path.clone(),
+        source,
+    })?;
+    let mut manifest: MarketplaceManifest =
+        serde_json::from_str(&before).map_err(|source| PluginError::ConfigParseError {
+            path: path.clone(),
+            source,
+        })?;
+    sync_plugins(&mut manifest, dir);
+
+    let mut plan = ChangePlan::new();
+    plan.push(FileChange {
+        after: to_json(&path, &manifest)?,
+        path,
+        before: Some(before),
+    });
+    Ok(plan)
+}
+
+pub fn marketplace_manifest_path(dir: &Path) -> PathBuf {
+    dir.join(".claude-plugin").join("marketplace.json")
+}
+
+/// Plugins inside a marketplace directory: the directory itself, its subdirectories
+/// and `plugins/*`, as (source path, manifest) pairs sorted by source
+pub fn find_local_plugins(dir: &Path) -> Vec<(String, PluginManifest)> {
+    let mut candidates = vec![("./".to_string(), dir.to_path_buf())];
+    for prefix in ["", "plugins/"] {
+        let Ok(entries) = fs::read_dir(dir.join(prefix)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with('.') && entry.path().is_dir() {
+                candidates.push((format!("./{}{}", prefix, name), entry.path()));
+            }
+        }
+    }
+
+    let mut plugins: Vec<(String, PluginManifest)> = candidates
+        .into_iter()
+        .filter_map(|(source, path)| {
+            let text = fs::read_to_string(path.join(".claude-plugin").join("plugin.json")).ok()?;
+            Some((source, serde_json::from_str(&text).ok()?))
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.0.cmp(&b.0));
+    plugins
+}
+
+fn sync_plugins(manifest: &mut MarketplaceManifest, dir: &Path) {
+    let found = find_local_plugins(dir);
+
+    // Local entries whose plugin is gone are dropped; remote ones are kept
+    manifest.plugins.retain(|entry| match entry.local_source() {
+        Some(source) => found
+            .iter()
+            .any(|(s, m)| s == source || m.name == entry.name),
+        None => true,
+    });
+
+    for (source, plugin) in found {
+        let existing = manifest
+            .plugins
+            .iter_mut()
+            .find(|e| e.name == plugin.name || e.local_source() == Some(source.as_str()));
+        let entry = match existing {
+            Some(entry) => entry,
+            None => {
+                manifest.plugins.push(MarketplacePlugin {
+                    name: plugin.name.clone(),
+                    source: source.clone().into(),
+                    description: None,
+                    version: None,
+                    other: BTreeMap::new(),
+                });
+                manifest.plugins.last_mut().expect("just pushed")
+            }
+        };
+        entry.name = plugin.name;
+        entry.source = source.into();
+        if plugin.description.is_some() {
+            entry.description = plugin.description;
+        }
+        if plugin.version.is_some() {
+            entry.version = plugin.version;
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+fn to_json<T: serde::Serialize>(path: &Path, data: &T) -> Result<String> {
+    serde_json::to_string_pretty(data)
+        .map(|json| json + "\n")
+        .map_err(|source| PluginError::ConfigParseError {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{validate_plugin, PluginContents};
+    use tempfile::TempDir;
+
+    fn write_plan(plan: &ChangePlan) {
+        for change in &plan.changes {
+            fs::create_dir_all(change.path.parent().unwrap()).unwrap();
+            fs::write(&change.path, &change.after).unwrap();
+        }
+    }
+
+    fn new_plugin(parent: &Path, name: &str) {
+        let options = NewPlugin {
+            name: name.to_string(),
+            description: Some(format!("The {} plugin", name)),
+            author: Some("Dev Team".to_string()),
+        };
+        write_plan(&plan_new_plugin(parent, &options).unwrap());
+    }
+
+    #[test]
+    fn test_new_plugin_passes_validation() {
+        let temp = TempDir::new().unwrap();
+        new_plugin(temp.path(), "review-tools");
+
+        let root = temp.path().join("review-tools");
+        assert_eq!(validate_plugin(&root), Vec::new());
+
+        let contents = PluginContents::scan(&root, None);
+        assert_eq!(contents.commands[0].name, "hello");
+        assert_eq!(contents.agents[0].name, "review-tools-agent");
+        assert_eq!(contents.hooks.len(), 1);
+        assert!(contents.hooks[0].risks.is_empty());
+    }
+
+    #[test]
+    fn test_new_plugin_rejects_bad_names_and_existing_dirs() {
+        let temp = TempDir::new().unwrap();
+        let options = NewPlugin {
+            name: "Review Tools".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            plan_new_plugin(temp.path(), &options),
+            Err(PluginError::InvalidName(_))
+        ));
+
+        new_plugin(temp.path(), "taken");
+        let options = NewPlugin {
+            name: "taken".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            plan_new_plugin(temp.path(), &options),
+            Err(PluginError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_marketplace_lists_plugins() {
+        let temp = TempDir::new().unwrap();
+        new_plugin(&temp.path().join("plugins"), "alpha");
+        new_plugin(temp.path(), "beta");
+
+        let plan = plan_new_marketplace(temp.path(), Some("team-tools"), Some("Dev Team")).unwrap();
+        write_plan(&plan);
+
+        let manifest: MarketplaceManifest = serde_json::from_str(&plan.changes[0].after).unwrap();
+        assert_eq!(manifest.name, "team-tools");
+        let sources: Vec<_> = manifest
+            .plugins
+            .iter()
+            .map(|p| (p.name.as_str(), p.local_source().unwrap()))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![("beta", "./beta"), ("alpha", "./plugins/alpha")]
+        );
+        assert_eq!(manifest.plugins[0].version.as_deref(), Some("0.1.0"));
+
+        assert!(matches!(
+            plan_new_marketplace(temp.path(), None, None),
+            Err(PluginError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_marketplace_keeps_extra_fields() {
+        let temp = TempDir::new().unwrap();
+        new_plugin(temp.path(), "alpha");
+        new_plugin(temp.path(), "beta");
+        fs::create_dir_all(temp.path().join(".claude-plugin")).unwrap();
+        fs::write(
+            marketplace_manifest_path(temp.path()),
+            r#"{
+  "name": "team",
+  "metadata": {"version": "2"},
+  "plugins": [
+    {"name": "alpha", "source": "./alpha", "category": "review"},
+    {"name": "gone", "source": "./gone"},
+    {"name": "remote", "source": {"source": "github", "repo": "org/remote"}}
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let plan = plan_build_marketplace(temp.path()).unwrap();
+        let manifest: MarketplaceManifest = serde_json::from_str(&plan.changes[0].after).unwrap();
+        let names: Vec<_> = manifest.plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "remote", "beta"]);
+        assert_eq!(manifest.plugins[0].other["category"], "review");
+        assert_eq!(
+            manifest.plugins[0].description.as_deref(),
+            Some("The alpha plugin")
+        );
+        assert_eq!(manifest.other["metadata"]["version"], "2");
+
+        // Rebuilding a fresh catalog changes nothing
+        write_plan(&plan);
+        assert!(plan_build_marketplace(temp.path()).unwrap().is_empty());
+    }
+}
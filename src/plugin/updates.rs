@@ -0,0 +1,466 @@
+//! Update checks against the local marketplace checkouts.
+//!
+//! Nothing is fetched: the version and commit recorded in installed_plugins.json
+//! are compared with the marketplace catalog and the checkout's git HEAD as they
+//! are on disk, i.e. as of the marketplace's last update. `ccpm update` fetches
+//! the checkout first, then copies the plugin from it into a new cache directory.
+
+use super::{config::Pin, PluginError, Result};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateStatus {
+    UpToDate,
+    /// The catalog lists a newer version
+    NewerVersion,
+    /// Same version, but the checkout is at a different commit than the install
+    DifferentCommit,
+    /// Not in the catalog, or the marketplace is not checked out
+    Unknown,
+}
+
+impl UpdateStatus {
+    pub fn has_update(&self) -> bool {
+        matches!(
+            self,
+            UpdateStatus::NewerVersion | UpdateStatus::DifferentCommit
+        )
+    }
+}
+
+impl std::fmt::Display for UpdateStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateStatus::UpToDate => write!(f, "up to date"),
+            UpdateStatus::NewerVersion => write!(f, "newer version available"),
+            UpdateStatus::DifferentCommit => write!(f, "different commit available"),
+            UpdateStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Update status of one installed plugin
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdate {
+    pub plugin_id: String,
+    pub installed_version: String,
+    pub available_version: Option<String>,
+    pub installed_commit: Option<String>,
+    pub available_commit: Option<String>,
+    pub status: UpdateStatus,
+    /// Set by `ccpm pin`
+    pub pin: Option<Pin>,
+    /// Plugin directory in the marketplace checkout; None when its source lives elsewhere
+    #[serde(skip)]
+    pub source_dir: Option<PathBuf>,
+}
+
+impl PluginUpdate {
+    pub fn new(
+        plugin_id: String,
+        installed_version: String,
+        installed_commit: Option<String>,
+        available_version: Option<String>,
+        available_commit: Option<String>,
+    ) -> Self {
+        let status = update_status(
+            &installed_version,
+            installed_commit.as_deref(),
+            available_version.as_deref(),
+            available_commit.as_deref(),
+        );
+        Self {
+            plugin_id,
+            installed_version,
+            available_version,
+            installed_commit,
+            available_commit,
+            status,
+            pin: None,
+            source_dir: None,
+        }
+    }
+
+    /// An update is available but the pin does not allow it
+    pub fn is_held(&self) -> bool {
+        let version = self
+            .available_version
+            .as_deref()
+            .unwrap_or(&self.installed_version);
+        self.status.has_update()
+            && self
+                .pin
+                .as_ref()
+                .is_some_and(|pin| !pin.allows(version, self.available_commit.as_deref()))
+    }
+
+    /// An update is available and not held back by a pin
+    pub fn can_update(&self) -> bool {
+        self.status.has_update() && !self.is_held()
+    }
+
+    /// What is available, e.g. `1.2.0` or `1.1.0 (3f2a9c1)` for a commit change;
+    /// `-` when unknown
+    pub fn available_display(&self) -> String {
+        let version = self
+            .available_version
+            .as_deref()
+            .unwrap_or(&self.installed_version);
+        match (self.status, &self.available_commit) {
+            (UpdateStatus::Unknown, _) => "-".to_string(),
+            (UpdateStatus::DifferentCommit, Some(commit)) => {
+                format!("{} ({})", version, short_commit(commit))
+            }
+            _ => version.to_string(),
+        }
+    }
+}
+
+/// A newer version wins over a commit change; without anything to compare
+/// against the status is unknown
+pub fn update_status(
+    installed_version: &str,
+    installed_commit: Option<&str>,
+    available_version: Option<&str>,
+    available_commit: Option<&str>,
+) -> UpdateStatus {
+    if let Some(available) = available_version {
+        match compare_versions(available, installed_version) {
+            Some(Ordering::Greater) => return UpdateStatus::NewerVersion,
+            // Unparseable versions: any change counts as an update
+            None if available != installed_version => return UpdateStatus::NewerVersion,
+            _ => {}
+        }
+    }
+    if let (Some(installed), Some(available)) = (installed_commit, available_commit) {
+        if !same_commit(installed, available) {
+            return UpdateStatus::DifferentCommit;
+        }
+    }
+    if available_version.is_none() && available_commit.is_none() {
+        return UpdateStatus::Unknown;
+    }
+    UpdateStatus::UpToDate
+}
+
+/// Compare `major.minor.patch[-pre]` versions. Missing components count as 0 and a
+/// release sorts after its pre-releases. None if either is not numeric.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = split_version(a)?;
+    let (b_core, b_pre) = split_version(b)?;
+    let len = a_core.len().max(b_core.len());
+    for i in 0..len {
+        let ordering = a_core.get(i).unwrap_or(&0).cmp(b_core.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(b),
+    })
+}
+
+fn split_version(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let version = version.trim().trim_start_matches('v');
+    // Build metadata does not affect ordering
+    let version = version.split('+').next().unwrap_or(version);
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let parts = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((parts, pre))
+}
+
+/// Full and abbreviated hashes of the same commit match
+pub(crate) fn same_commit(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim(), b.trim());
+    if a.len() < 7 || b.len() < 7 {
+        return a == b;
+    }
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// First seven characters of a commit hash
+pub fn short_commit(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+/// The commit checked out in `repo`, read from `.git` without running git.
+/// Handles detached heads, loose and packed refs, and `.git` files (worktrees,
+/// submodules).
+pub fn git_head(repo: &Path) -> Option<String> {
+    let git_dir = git_dir(repo)?;
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => {
+            // Worktrees keep their branches in the main repository
+            let common_dir = fs::read_to_string(git_dir.join("commondir"))
+                .ok()
+                .map(|dir| git_dir.join(dir.trim()));
+            std::iter::once(git_dir.as_path())
+                .chain(common_dir.as_deref())
+                .find_map(|dir| resolve_ref(dir, reference))
+        }
+        None if !head.is_empty() => Some(head.to_string()),
+        None => None,
+    }
+}
+
+fn git_dir(repo: &Path) -> Option<PathBuf> {
+    let dot_git = repo.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(repo.join(target))
+}
+
+fn resolve_ref(git_dir: &Path, reference: &str) -> Option<String> {
+    if let Ok(sha) = fs::read_to_string(git_dir.join(reference)) {
+        let sha = sha.trim();
+        if !sha.is_empty() {
+            return Some(sha.to_string());
+        }
+    }
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+        .find_map(|line| match line.split_once(' ') {
+            Some((sha, name)) if name.trim() == reference => Some(sha.to_string()),
+            _ => None,
+        })
+}
+
+/// Fetch `git_ref` (the remote's default branch if None) from `origin` and move
+/// the checkout to it. Returns the new HEAD.
+pub fn fetch_checkout(checkout: &Path, git_ref: Option<&str>) -> Result<String> {
+    let git_ref = git_ref.unwrap_or("HEAD");
+    check_ref(git_ref)?;
+    git(
+        checkout,
+        &["fetch", "--quiet", "--end-of-options", "origin", git_ref],
+    )?;
+    git(checkout, &["reset", "--quiet", "--hard", "FETCH_HEAD"])?;
+    git_head(checkout).ok_or_else(|| PluginError::GitFailed {
+        path: checkout.to_path_buf(),
+        message: "no HEAD after fetch".to_string(),
+    })
+}
+
+/// Refs come from known_marketplaces.json or a project's settings; one starting
+/// with `-` would be read by git as an option such as `--upload-pack`
+pub(crate) fn check_ref(git_ref: &str) -> Result<()> {
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err(PluginError::InvalidRef(git_ref.to_string()));
+    }
+    Ok(())
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<()> {
+    git_output(repo, args).map(|_| ())
+}
+
+/// Run git in `repo` and return its stdout
+pub(crate) fn git_output(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| PluginError::GitFailed {
+            path: repo.to_path_buf(),
+            message: e.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(PluginError::GitFailed {
+            path: repo.to_path_buf(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Cache directory name for an installed version, e.g. `1.2.0-3f2a9c1`. The
+/// commit keeps same-version updates from overwriting the copy kept for rollback.
+pub fn cache_dir_name(version: &str, commit: Option<&str>) -> String {
+    match commit {
+        Some(commit) => format!("{}-{}", version, short_commit(commit)),
+        None => version.to_string(),
+    }
+}
+
+/// Copy a plugin directory, leaving out `.git`
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SHA_A: &str = "3f2a9c1d0e5b7a8c9d0e1f2a3b4c5d6e7f8a9b0c";
+    const SHA_B: &str = "9b0c3f2a9c1d0e5b7a8c9d0e1f2a3b4c5d6e7f8a";
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.0", "1.10.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("v2.0", "1.9.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("1.0", "1.0.0"), Some(Ordering::Equal));
+        assert_eq!(
+            compare_versions("1.0.0", "1.0.0-beta.1"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_versions("1.0.0+build.5", "1.0.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_versions("latest", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_update_status() {
+        assert_eq!(
+            update_status("1.0.0", Some(SHA_A), Some("1.1.0"), Some(SHA_A)),
+            UpdateStatus::NewerVersion
+        );
+        assert_eq!(
+            update_status("1.0.0", Some(SHA_A), Some("1.0.0"), Some(SHA_B)),
+            UpdateStatus::DifferentCommit
+        );
+        assert_eq!(
+            update_status("1.0.0", Some(&SHA_A[..7]), Some("1.0.0"), Some(SHA_A)),
+            UpdateStatus::UpToDate
+        );
+        // An older catalog version is not an update
+        assert_eq!(
+            update_status("2.0.0", None, Some("1.0.0"), None),
+            UpdateStatus::UpToDate
+        );
+        assert_eq!(
+            update_status("nightly", None, Some("stable"), None),
+            UpdateStatus::NewerVersion
+        );
+        assert_eq!(
+            update_status("1.0.0", Some(SHA_A), None, None),
+            UpdateStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_pin_holds_updates() {
+        let pin = |version: Option<&str>, commit: Option<&str>| Pin {
+            version: version.map(str::to_string),
+            commit: commit.map(str::to_string),
+            pinned_at: String::new(),
+        };
+        let mut update = PluginUpdate::new(
+            "p@m".into(),
+            "1.0.0".into(),
+            Some(SHA_A.into()),
+            Some("1.1.0".into()),
+            Some(SHA_B.into()),
+        );
+        assert!(update.can_update());
+
+        update.pin = Some(pin(Some("1.0.0"), Some(SHA_A)));
+        assert!(update.is_held());
+        assert!(!update.can_update());
+
+        // A pin to the available version lets the update through
+        update.pin = Some(pin(Some("1.1.0"), None));
+        assert!(update.can_update());
+        update.pin = Some(pin(None, Some(&SHA_B[..7])));
+        assert!(update.can_update());
+    }
+
+    #[test]
+    fn test_fetch_rejects_option_refs() {
+        let temp = TempDir::new().unwrap();
+        let marker = temp.path().join("ran");
+        let git_ref = format!("--upload-pack=touch {}", marker.display());
+        assert!(matches!(
+            fetch_checkout(temp.path(), Some(&git_ref)),
+            Err(PluginError::InvalidRef(_))
+        ));
+        assert!(matches!(
+            crate::plugin::marketplace::clone_checkout(
+                "file:///nonexistent",
+                Some(&git_ref),
+                &temp.path().join("clone")
+            ),
+            Err(PluginError::InvalidRef(_))
+        ));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_git_head() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path();
+        let git = repo.join(".git");
+        fs::create_dir_all(git.join("refs/heads")).unwrap();
+        assert_eq!(git_head(&repo.join("missing")), None);
+
+        // Loose ref
+        fs::write(git.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(git.join("refs/heads/main"), format!("{}\n", SHA_A)).unwrap();
+        assert_eq!(git_head(repo).as_deref(), Some(SHA_A));
+
+        // Packed ref
+        fs::remove_file(git.join("refs/heads/main")).unwrap();
+        fs::write(
+            git.join("packed-refs"),
+            format!("# pack-refs with: peeled\n{} refs/heads/main\n", SHA_B),
+        )
+        .unwrap();
+        assert_eq!(git_head(repo).as_deref(), Some(SHA_B));
+
+        // Detached head
+        fs::write(git.join("HEAD"), format!("{}\n", SHA_A)).unwrap();
+        assert_eq!(git_head(repo).as_deref(), Some(SHA_A));
+    }
+}
@@ -0,0 +1,665 @@
+//! Lint a plugin directory the way a plugin author would before publishing it.
+//!
+//! Checks `.claude-plugin/plugin.json` against the manifest model, the files it
+//! references, command/agent/skill frontmatter, duplicate names and hooks configs.
+//! Diagnostics carry a file and 1-based line so they read like compiler output.
+
+use super::{
+    audit::plugin_script_refs,
+    config::PluginManifest,
+    contents::{ContentKind, PluginContents},
+    frontmatter::Frontmatter,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Hook events Claude Code dispatches
+pub const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+const MANIFEST_FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "version",
+    "author",
+    "homepage",
+    "repository",
+    "license",
+    "keywords",
+    "commands",
+    "agents",
+    "hooks",
+    "mcpServers",
+];
+
+/// Manifest fields that point at files or directories in the plugin
+const PATH_FIELDS: &[&str] = &["commands", "agents", "hooks", "mcpServers"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// One problem found in a plugin directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: PathBuf,
+    /// 1-based; problems with a whole file point at line 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.severity,
+            self.message
+        )
+    }
+}
+
+/// Validate the plugin rooted at `root`. Diagnostics are sorted by file and line.
+pub fn validate_plugin(root: &Path) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        root,
+        diagnostics: Vec::new(),
+    };
+
+    let manifest = validator.check_manifest();
+    let contents = PluginContents::scan(root, manifest.as_ref());
+    validator.check_frontmatter(&contents);
+    validator.check_duplicates(&contents);
+    validator.check_hooks(manifest.as_ref());
+
+    let mut diagnostics = validator.diagnostics;
+    diagnostics.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    diagnostics.dedup();
+    diagnostics
+}
+
+struct Validator<'a> {
+    root: &'a Path,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, severity: Severity, path: &Path, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            path: path.to_path_buf(),
+            line,
+            message,
+        });
+    }
+
+    fn error(&mut self, path: &Path, line: usize, message: String) {
+        self.push(Severity::Error, path, line, message);
+    }
+
+    fn warning(&mut self, path: &Path, line: usize, message: String) {
+        self.push(Severity::Warning, path, line, message);
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.root.join(".claude-plugin").join("plugin.json")
+    }
+
+    /// Parse the manifest, reporting each field that does not fit the model.
+    /// Returns the manifest when it parses, so the remaining checks can use it.
+    fn check_manifest(&mut self) -> Option<PluginManifest> {
+        let path = self.manifest_path();
+        let Ok(source) = fs::read_to_string(&path) else {
+            self.error(&path, 1, "missing plugin manifest".to_string());
+            return None;
+        };
+
+        let value: Value = match serde_json::from_str(&source) {
+            Ok(value) => value,
+            Err(e) => {
+                self.error(
+                    &path,
+                    e.line().max(1),
+                    format!("invalid JSON: {}", json_message(&e)),
+                );
+                return None;
+            }
+        };
+        let Some(object) = value.as_object() else {
+            self.error(&path, 1, "manifest must be a JSON object".to_string());
+            return None;
+        };
+
+        for key in object.keys() {
+            if !MANIFEST_FIELDS.contains(&key.as_str()) {
+                self.warning(
+                    &path,
+                    key_line(&source, key),
+                    format!("unknown manifest field \"{}\"", key),
+                );
+            }
+        }
+
+        match object.get("name") {
+            None => self.error(&path, 1, "missing required field \"name\"".to_string()),
+            Some(Value::String(name)) if !is_kebab_case(name) => self.warning(
+                &path,
+                key_line(&source, "name"),
+                format!("name \"{}\" should be kebab-case", name),
+            ),
+            _ => {}
+        }
+
+        // Deserialize each known field on its own so type errors point at their line
+        for (key, field) in object {
+            if !MANIFEST_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            let mut single = serde_json::Map::new();
+            single.insert("name".to_string(), Value::String(String::new()));
+            single.insert(key.clone(), field.clone());
+            if let Err(e) = serde_json::from_value::<PluginManifest>(Value::Object(single)) {
+                self.error(
+                    &path,
+                    key_line(&source, key),
+                    format!("invalid \"{}\": {}", key, e),
+                );
+            }
+        }
+
+        for key in PATH_FIELDS {
+            let paths: Vec<String> = match object.get(*key) {
+                Some(Value::String(p)) => vec![p.clone()],
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .filter_map(|p| p.as_str().map(String::from))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for relative in paths {
+                let line = string_line(&source, &relative);
+                if !relative.starts_with("./") {
+                    self.warning(
+                        &path,
+                        line,
+                        format!("\"{}\" path \"{}\" should start with ./", key, relative),
+                    );
+                }
+                if !self.root.join(&relative).exists() {
+                    self.error(
+                        &path,
+                        line,
+                        format!("\"{}\" path \"{}\" does not exist", key, relative),
+                    );
+                }
+            }
+        }
+
+        let manifest = serde_json::from_value::<PluginManifest>(value.clone()).ok()?;
+
+        if let Some(ref version) = manifest.version {
+            if !is_semver(version) {
+                self.warning(
+                    &path,
+                    key_line(&source, "version"),
+                    format!(
+                        "version \"{}\" is not semantic (MAJOR.MINOR.PATCH)",
+                        version
+                    ),
+                );
+            }
+        }
+
+        Some(manifest)
+    }
+
+    /// Commands should have a description; agents and skills need a name and description
+    fn check_frontmatter(&mut self, contents: &PluginContents) {
+        for (kind, item) in contents.items() {
+            let Ok(text) = fs::read_to_string(&item.path) else {
+                continue;
+            };
+            let required: &[&str] = match kind {
+                ContentKind::Command => &[],
+                _ => &["name", "description"],
+            };
+
+            let Some(frontmatter) = Frontmatter::parse(&text) else {
+                if text.trim_start_matches('\u{feff}').starts_with("---") {
+                    self.error(
+                        &item.path,
+                        1,
+                        "frontmatter is missing its closing ---".to_string(),
+                    );
+                } else if !required.is_empty() {
+                    self.error(
+                        &item.path,
+                        1,
+                        format!(
+                            "{} has no frontmatter (requires {})",
+                            kind,
+                            required.join(", ")
+                        ),
+                    );
+                } else {
+                    self.warning(&item.path, 1, "command has no description".to_string());
+                }
+                continue;
+            };
+
+            for key in required {
+                match frontmatter.lines.get(*key) {
+                    None => self.error(
+                        &item.path,
+                        1,
+                        format!("{} frontmatter is missing \"{}\"", kind, key),
+                    ),
+                    Some(&line) if frontmatter.get(key).is_none() => {
+                        self.error(&item.path, line, format!("\"{}\" is empty", key))
+                    }
+                    _ => {}
+                }
+            }
+            if kind == ContentKind::Command && frontmatter.get("description").is_none() {
+                let line = frontmatter.lines.get("description").copied().unwrap_or(1);
+                self.warning(&item.path, line, "command has no description".to_string());
+            }
+        }
+    }
+
+    /// Two commands (or agents, or skills) with the same name shadow each other
+    fn check_duplicates(&mut self, contents: &PluginContents) {
+        let mut first: HashMap<(ContentKind, String), PathBuf> = HashMap::new();
+        for (kind, item) in contents.items() {
+            let key = (kind, item.name.to_lowercase());
+            match first.get(&key) {
+                Some(original) => {
+                    let line = fs::read_to_string(&item.path)
+                        .ok()
+                        .and_then(|text| Frontmatter::parse(&text))
+                        .and_then(|f| f.lines.get("name").copied())
+                        .unwrap_or(1);
+                    let message = format!(
+                        "duplicate {} name {} (also defined in {})",
+                        kind,
+                        kind.invocation(&item.name),
+                        original.display()
+                    );
+                    self.error(&item.path, line, message);
+                }
+                None => {
+                    first.insert(key, item.path.clone());
+                }
+            }
+        }
+    }
+
+    /// Hook configs: `hooks/hooks.json`, manifest paths and inline manifest hooks
+    fn check_hooks(&mut self, manifest: Option<&PluginManifest>) {
+        let mut files = vec![self.root.join("hooks").join("hooks.json")];
+        if let Some(hooks) = manifest.and_then(|m| m.hooks.as_ref()) {
+            files.extend(hooks.paths().iter().map(|p| self.root.join(p)));
+            if let Some(inline) = hooks.inline() {
+                let path = self.manifest_path();
+                let source = fs::read_to_string(&path).unwrap_or_default();
+                self.check_hooks_config(inline, &path, &source);
+            }
+        }
+
+        let mut seen = Vec::new();
+        for file in files {
+            let key: PathBuf = file.components().collect();
+            if seen.contains(&key) || !file.is_file() {
+                continue;
+            }
+            seen.push(key);
+
+            let Ok(source) = fs::read_to_string(&file) else {
+                continue;
+            };
+            match serde_json::from_str::<Value>(&source) {
+                Ok(value) => self.check_hooks_config(&value, &file, &source),
+                Err(e) => self.error(
+                    &file,
+                    e.line().max(1),
+                    format!("invalid JSON: {}", json_message(&e)),
+                ),
+            }
+        }
+    }
+
+    fn check_hooks_config(&mut self, value: &Value, path: &Path, source: &str) {
+        let events = value.get("hooks").unwrap_or(value);
+        let Some(events) = events.as_object() else {
+            self.error(
+                path,
+                1,
+                "hooks must be an object keyed by event name".to_string(),
+            );
+            return;
+        };
+
+        for (event, groups) in events {
+            let line = key_line(source, event);
+            if !HOOK_EVENTS.contains(&event.as_str()) {
+                self.error(
+                    path,
+                    line,
+                    format!(
+                        "unknown hook event \"{}\" (expected one of {})",
+                        event,
+                        HOOK_EVENTS.join(", ")
+                    ),
+                );
+            }
+            let Some(groups) = groups.as_array() else {
+                self.error(path, line, format!("\"{}\" must be an array", event));
+                continue;
+            };
+
+            for hook in groups.iter().flat_map(|g| {
+                g.get("hooks")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+            }) {
+                match hook.get("type").and_then(Value::as_str) {
+                    Some("command") => {}
+                    Some("prompt") => continue,
+                    Some(other) => {
+                        self.error(
+                            path,
+                            string_line(source, other),
+                            format!("unknown hook type \"{}\"", other),
+                        );
+                        continue;
+                    }
+                    None => {
+                        self.error(path, line, format!("{} hook has no \"type\"", event));
+                        continue;
+                    }
+                }
+
+                let Some(command) = hook.get("command").and_then(Value::as_str) else {
+                    self.error(
+                        path,
+                        line,
+                        format!("{} command hook has no \"command\"", event),
+                    );
+                    continue;
+                };
+                for script in plugin_script_refs(command, self.root) {
+                    if !script.exists() {
+                        let relative = script.strip_prefix(self.root).unwrap_or(&script);
+                        self.error(
+                            path,
+                            string_line(source, command),
+                            format!("hook script {} does not exist", relative.display()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// serde_json's message without the trailing "at line X column Y"
+fn json_message(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    match message.rfind(" at line ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message,
+    }
+}
+
+/// Line of the first `"key":` in the source
+fn key_line(source: &str, key: &str) -> usize {
+    let quoted = serde_json::to_string(key).unwrap_or_default();
+    let mut from = 0;
+    while let Some(pos) = source[from..].find(&quoted) {
+        let end = from + pos + quoted.len();
+        if source[end..].trim_start().starts_with(':') {
+            return line_at(source, from + pos);
+        }
+        from = end;
+    }
+    1
+}
+
+/// Line of the first occurrence of a JSON string value in the source
+fn string_line(source: &str, value: &str) -> usize {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    source
+        .find(&quoted)
+        .map(|pos| line_at(source, pos))
+        .unwrap_or(1)
+}
+
+fn line_at(source: &str, pos: usize) -> usize {
+    source[..pos].matches('\n').count() + 1
+}
+
+pub(crate) fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// MAJOR.MINOR.PATCH with an optional -prerelease or +build suffix
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn valid_plugin() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            ".claude-plugin/plugin.json",
+            "{\n  \"name\": \"review-tools\",\n  \"version\": \"1.2.0\",\n  \"hooks\": \"./hooks/hooks.json\"\n}\n",
+        );
+        write(
+            root,
+            "commands/review.md",
+            "---\ndescription: Review the diff\n---\nReview it",
+        );
+        write(
+            root,
+            "agents/reviewer.md",
+            "---\nname: reviewer\ndescription: Senior reviewer\n---\n",
+        );
+        write(
+            root,
+            "hooks/hooks.json",
+            r#"{"hooks": {"PostToolUse": [{"matcher": "Edit", "hooks": [{"type": "command", "command": "${CLAUDE_PLUGIN_ROOT}/scripts/fmt.sh"}]}]}}"#,
+        );
+        write(root, "scripts/fmt.sh", "#!/bin/sh\n");
+        temp
+    }
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<String> {
+        diagnostics.iter().map(|d| d.message.clone()).collect()
+    }
+
+    #[test]
+    fn test_valid_plugin_has_no_diagnostics() {
+        let temp = valid_plugin();
+        assert_eq!(validate_plugin(temp.path()), Vec::new());
+    }
+
+    #[test]
+    fn test_missing_manifest() {
+        let temp = TempDir::new().unwrap();
+        let diagnostics = validate_plugin(temp.path());
+        assert_eq!(messages(&diagnostics), vec!["missing plugin manifest"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_manifest_problems_point_at_their_line() {
+        let temp = valid_plugin();
+        write(
+            temp.path(),
+            ".claude-plugin/plugin.json",
+            "{\n  \"name\": \"Review Tools\",\n  \"keywords\": \"review\",\n  \"agents\": \"./missing\",\n  \"extra\": 1\n}\n",
+        );
+        let diagnostics = validate_plugin(temp.path());
+        let summary: Vec<(usize, Severity)> =
+            diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, Severity::Warning),
+                (3, Severity::Error),
+                (4, Severity::Error),
+                (5, Severity::Warning),
+            ]
+        );
+        assert!(diagnostics[2]
+            .message
+            .contains("\"./missing\" does not exist"));
+        assert!(diagnostics[0]
+            .to_string()
+            .ends_with("plugin.json:2: warning: name \"Review Tools\" should be kebab-case"));
+    }
+
+    #[test]
+    fn test_invalid_json_reports_line() {
+        let temp = valid_plugin();
+        write(
+            temp.path(),
+            ".claude-plugin/plugin.json",
+            "{\n  \"name\": \"x\",\n}\n",
+        );
+        let diagnostics = validate_plugin(temp.path());
+        assert_eq!(diagnostics[0].line, 3);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("invalid JSON: trailing comma"));
+    }
+
+    #[test]
+    fn test_frontmatter_required_keys() {
+        let temp = valid_plugin();
+        write(
+            temp.path(),
+            "agents/helper.md",
+            "---\nname: helper\n---\nHelp",
+        );
+        write(temp.path(), "agents/broken.md", "---\nname: broken\n");
+        write(temp.path(), "commands/plain.md", "No frontmatter");
+        write(
+            temp.path(),
+            "skills/pdf/SKILL.md",
+            "---\nname: pdf\ndescription:\n---\n",
+        );
+
+        let diagnostics = validate_plugin(temp.path());
+        assert_eq!(
+            messages(&diagnostics),
+            vec![
+                "frontmatter is missing its closing ---",
+                "agent frontmatter is missing \"description\"",
+                "command has no description",
+                "\"description\" is empty",
+            ]
+        );
+        assert_eq!(diagnostics[3].line, 3);
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let temp = valid_plugin();
+        write(
+            temp.path(),
+            "agents/copy.md",
+            "---\nname: Reviewer\ndescription: Same name\n---\n",
+        );
+        let diagnostics = validate_plugin(temp.path());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("duplicate agent name reviewer (also defined in"));
+    }
+
+    #[test]
+    fn test_hook_events_and_scripts() {
+        let temp = valid_plugin();
+        write(
+            temp.path(),
+            "hooks/hooks.json",
+            "{\n  \"hooks\": {\n    \"PreToolUse\": [{\"hooks\": [{\"type\": \"command\", \"command\": \"${CLAUDE_PLUGIN_ROOT}/scripts/gone.sh\"}]}],\n    \"OnSave\": []\n  }\n}\n",
+        );
+        let diagnostics = validate_plugin(temp.path());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.line, d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "hook script scripts/gone.sh does not exist"),
+                (
+                    4,
+                    "unknown hook event \"OnSave\" (expected one of PreToolUse, PostToolUse, Notification, UserPromptSubmit, Stop, SubagentStop, PreCompact, SessionStart, SessionEnd)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_semver() {
+        assert!(is_semver("1.0.0"));
+        assert!(is_semver("2.10.3-beta.1"));
+        assert!(!is_semver("1.0"));
+        assert!(!is_semver("v1.0.0"));
+    }
+}
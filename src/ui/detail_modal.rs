@@ -1,4 +1,8 @@
 use crate::app::App;
+use crate::plugin::{
+    short_commit, ChangeKind, Changelog, Collision, McpCheckReport, Plugin, PluginContents,
+    RiskFlag,
+};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -25,9 +29,16 @@ pub fn render_detail_modal(frame: &mut Frame, app: &App, area: Rect) {
                 format!("@{}", plugin.marketplace),
                 Style::default().fg(Color::DarkGray),
             )),
-            Line::from(""),
         ];
 
+        if let Some(ref source) = plugin.marketplace_source {
+            lines.push(Line::from(Span::styled(
+                format!("from {}", source),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+
         // Status section
         lines.push(Line::from(vec![
             Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -99,6 +110,8 @@ pub fn render_detail_modal(frame: &mut Frame, app: &App, area: Rect) {
             ]));
         }
 
+        lines.extend(metadata_lines(plugin));
+
         // Installation path
         if let Some(ref path) = plugin.install_path {
             lines.push(Line::from(vec![
@@ -136,6 +149,58 @@ pub fn render_detail_modal(frame: &mut Frame, app: &App, area: Rect) {
             lines.push(Line::from(Span::raw(description)));
         }
 
+        // Contents section
+        lines.push(Line::from(""));
+        lines.extend(contents_lines(
+            &plugin.contents,
+            app.mcp_checks_for(&plugin.id),
+        ));
+
+        // What an update would bring
+        match app.changelog_for(&plugin.id) {
+            Some(Ok(changelog)) => {
+                lines.push(Line::from(""));
+                lines.extend(changelog_lines(changelog));
+            }
+            Some(Err(e)) => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("Changelog unavailable: {}", e),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            None => {}
+        }
+
+        // Collisions with other active plugins
+        let collisions: Vec<&Collision> = app.collisions_for(&plugin.id).collect();
+        if !collisions.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Collisions:",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for collision in collisions {
+                let others: Vec<&str> = collision.others(&plugin.id).collect();
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "  {} ({})",
+                            collision.kind.invocation(&collision.name),
+                            collision.kind
+                        ),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("  also in {}", others.join(", ")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+        }
+
         // Footer with controls
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
@@ -164,6 +229,240 @@ pub fn render_detail_modal(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(modal, modal_area);
 }
 
+/// "Contents" section: what the plugin adds to a session, with MCP check results
+fn contents_lines<'a>(
+    contents: &'a PluginContents,
+    mcp_checks: &'a [McpCheckReport],
+) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Contents: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(contents.summary(), Style::default().fg(Color::DarkGray)),
+    ])];
+
+    let sections = [
+        ("Commands", "/", &contents.commands),
+        ("Agents", "", &contents.agents),
+        ("Skills", "", &contents.skills),
+    ];
+    for (title, prefix, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        lines.push(section_title(title));
+        for item in items {
+            let mut spans = vec![Span::styled(
+                format!("  {}{}", prefix, item.name),
+                Style::default().fg(Color::Green),
+            )];
+            if let Some(ref description) = item.description {
+                spans.push(Span::styled(
+                    format!("  {}", description),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    if !contents.hooks.is_empty() {
+        lines.push(section_title("Hooks"));
+        for hook in &contents.hooks {
+            let matcher = hook
+                .matcher
+                .as_ref()
+                .map(|m| format!(" [{}]", m))
+                .unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}{}", hook.event, matcher),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("  {}", hook.command.as_deref().unwrap_or(&hook.hook_type)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            lines.extend(risk_lines(&hook.risks));
+        }
+    }
+
+    if !contents.mcp_servers.is_empty() {
+        lines.push(section_title("MCP servers"));
+        for server in &contents.mcp_servers {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} ({})", server.name, server.transport()),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("  {}", server.target()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            if let Some(report) = mcp_checks.iter().find(|r| r.server == server.name) {
+                lines.extend(mcp_check_lines(report));
+            }
+        }
+        if has_stdio_servers(contents) {
+            lines.push(Line::from(Span::styled(
+                "  Press m to check stdio servers",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    lines
+}
+
+fn has_stdio_servers(contents: &PluginContents) -> bool {
+    contents
+        .mcp_servers
+        .iter()
+        .any(|s| s.transport() == "stdio")
+}
+
+/// Result of an MCP handshake check, indented under its server
+fn mcp_check_lines(report: &McpCheckReport) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    match report.error {
+        None => lines.push(Line::from(Span::styled(
+            format!(
+                "    ok: {} {}, {} tools: {}",
+                report.server_name.as_deref().unwrap_or("server"),
+                report.server_version.as_deref().unwrap_or("?"),
+                report.tools.len(),
+                report.tools.join(", ")
+            ),
+            Style::default().fg(Color::Green),
+        ))),
+        Some(ref error) => {
+            lines.push(Line::from(Span::styled(
+                format!("    failed: {}", error),
+                Style::default().fg(Color::Red),
+            )));
+            if let Some(last) = report.stderr.last() {
+                lines.push(Line::from(Span::styled(
+                    format!("    stderr: {}", last),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+    if !report.missing_env.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("    missing env: {}", report.missing_env.join(", ")),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    lines
+}
+
+/// "Changelog" section: commits since the installed one and changed components
+fn changelog_lines(changelog: &Changelog) -> Vec<Line<'_>> {
+    let mut lines = vec![Line::from(Span::styled(
+        "Changelog:",
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    if changelog.commits.is_empty() && changelog.changes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No changes since the installed commit",
+            Style::default().fg(Color::DarkGray),
+        )));
+        return lines;
+    }
+
+    if !changelog.commits.is_empty() {
+        lines.push(section_title("Commits"));
+        for commit in &changelog.commits {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} ", short_commit(&commit.sha)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(&commit.subject),
+                Span::styled(
+                    format!("  {}, {}", commit.author, commit.date),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+
+    if !changelog.changes.is_empty() {
+        lines.push(section_title("Changes"));
+        for change in &changelog.changes {
+            let color = match change.change {
+                ChangeKind::Added => Color::Green,
+                ChangeKind::Removed => Color::Red,
+                ChangeKind::Modified => Color::Yellow,
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} {} ", change.change.symbol(), change.component),
+                    Style::default().fg(color),
+                ),
+                Span::raw(&change.name),
+            ]));
+            lines.extend(risk_lines(&change.risks));
+        }
+    }
+    lines
+}
+
+/// Homepage, repository, license and keywords from the plugin manifest
+pub fn metadata_lines(plugin: &Plugin) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    for (label, value) in [
+        ("Homepage: ", &plugin.homepage),
+        ("Repository: ", &plugin.repository),
+        ("License: ", &plugin.license),
+    ] {
+        if let Some(value) = value {
+            lines.push(Line::from(vec![
+                Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(value),
+            ]));
+        }
+    }
+
+    if !plugin.keywords.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Keywords: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                plugin.keywords.join(", "),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    lines
+}
+
+/// One red line per risk flag, indented under its hook
+pub fn risk_lines(risks: &[RiskFlag]) -> Vec<Line<'_>> {
+    risks
+        .iter()
+        .map(|risk| {
+            Line::from(vec![
+                Span::styled(
+                    format!("    ! {}", risk.kind),
+                    Style::default().fg(Color::Red),
+                ),
+                Span::styled(
+                    format!("  {}", risk.evidence),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect()
+}
+
+fn section_title(title: &str) -> Line<'_> {
+    Line::from(Span::styled(
+        format!(" {}", title),
+        Style::default().fg(Color::Yellow),
+    ))
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::vertical([
         Constraint::Percentage((100 - percent_y) / 2),
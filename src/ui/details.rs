@@ -1,3 +1,4 @@
+use super::detail_modal::metadata_lines;
 use crate::app::App;
 use ratatui::{
     layout::Rect,
@@ -21,18 +22,53 @@ pub fn render_details(frame: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::raw(&plugin.marketplace),
             ]),
-            Line::from(vec![
-                Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
-                if plugin.is_enabled() {
-                    Span::styled("Enabled", Style::default().fg(Color::Green))
-                } else {
-                    Span::styled("Disabled", Style::default().fg(Color::Red))
-                },
-            ]),
         ];
 
+        if let Some(ref source) = plugin.marketplace_source {
+            lines.push(Line::from(vec![
+                Span::styled("Source: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(source.to_string(), Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
+            if plugin.is_dev {
+                Span::styled("Development", Style::default().fg(Color::Green))
+            } else if plugin.is_enabled() {
+                Span::styled("Enabled", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("Disabled", Style::default().fg(Color::Red))
+            },
+        ]));
+
+        if let Some(expiry) = app.expiry_for(&plugin.id) {
+            let previous = match expiry.previous {
+                Some(true) => "enabled",
+                Some(false) => "disabled",
+                None => "unset",
+            };
+            lines.push(Line::from(vec![
+                Span::styled("Expires: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!(
+                        "in {} at {}, then {} in {} scope",
+                        expiry.remaining(chrono::Utc::now()),
+                        expiry
+                            .expires_at
+                            .with_timezone(&chrono::Local)
+                            .format("%H:%M"),
+                        previous,
+                        expiry.scope
+                    ),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]));
+        }
+
         // Installation scope (where it's installed)
         let install_location = match (plugin.install_scope, plugin.is_current_project) {
+            _ if plugin.is_dev => "Dev (ccpm dev add)".to_string(),
             (crate::plugin::Scope::User, _) => "User (~/.claude)".to_string(),
             (crate::plugin::Scope::Project, true) => "Project (this project)".to_string(),
             (crate::plugin::Scope::Project, false) => "Project (other project)".to_string(),
@@ -77,6 +113,24 @@ pub fn render_details(frame: &mut Frame, app: &App, area: Rect) {
             ]));
         }
 
+        if let Some(update) = app.update_for(&plugin.id) {
+            if update.status.has_update() {
+                lines.push(Line::from(vec![
+                    Span::styled("Update: ", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        format!("{} ({})", update.available_display(), update.status),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                ]));
+            }
+            if let Some(ref pin) = update.pin {
+                lines.push(Line::from(vec![
+                    Span::styled("Pinned: ", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(pin.to_string(), Style::default().fg(Color::Magenta)),
+                ]));
+            }
+        }
+
         if let Some(ref author) = plugin.author {
             let author_text = if let Some(ref email) = author.email {
                 format!("{} <{}>", author.name, email)
@@ -89,6 +143,8 @@ pub fn render_details(frame: &mut Frame, app: &App, area: Rect) {
             ]));
         }
 
+        lines.extend(metadata_lines(plugin));
+
         if let Some(ref path) = plugin.install_path {
             lines.push(Line::from(vec![
                 Span::styled("Path: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -116,6 +172,32 @@ pub fn render_details(frame: &mut Frame, app: &App, area: Rect) {
             lines.push(Line::from(Span::raw(description)));
         }
 
+        // Validation results for dev plugins, refreshed on every reload
+        if plugin.is_dev {
+            lines.push(Line::from(""));
+            if plugin.diagnostics.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "Validation: no problems found",
+                    Style::default().fg(Color::Green),
+                )));
+            } else {
+                lines.push(Line::from(Span::styled(
+                    "Validation:",
+                    Style::default().add_modifier(Modifier::BOLD),
+                )));
+                for diagnostic in &plugin.diagnostics {
+                    let color = match diagnostic.severity {
+                        crate::plugin::Severity::Error => Color::Red,
+                        crate::plugin::Severity::Warning => Color::Yellow,
+                    };
+                    lines.push(Line::from(Span::styled(
+                        diagnostic.to_string(),
+                        Style::default().fg(color),
+                    )));
+                }
+            }
+        }
+
         lines
     } else {
         vec![Line::from(Span::styled(
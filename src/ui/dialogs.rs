@@ -1,59 +1,44 @@
+use super::detail_modal::risk_lines;
 use crate::app::{App, ConfirmAction};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
 pub fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction, area: Rect) {
+    match action {
+        ConfirmAction::Remove => render_remove_dialog(frame, app, area),
+        ConfirmAction::ApplyPlan => render_plan_dialog(frame, app, area),
+        ConfirmAction::EnableWithHooks => render_hooks_dialog(frame, app, area),
+    }
+}
+
+fn render_remove_dialog(frame: &mut Frame, app: &App, area: Rect) {
     let dialog_area = centered_rect(50, 30, area);
 
     // Clear the background
     frame.render_widget(Clear, dialog_area);
 
-    let (title, message) = match action {
-        ConfirmAction::Remove => {
-            let plugin_name = app
-                .selected_plugin()
-                .map(|p| p.display_name())
-                .unwrap_or_else(|| "unknown".to_string());
-            (
-                " Confirm Remove ",
-                format!("Are you sure you want to remove '{}'?", plugin_name),
-            )
-        }
-    };
+    let plugin_name = app
+        .selected_plugin()
+        .map(|p| p.display_name())
+        .unwrap_or_else(|| "unknown".to_string());
+    let message = format!("Are you sure you want to remove '{}'?", plugin_name);
 
     let content = vec![
         Line::from(""),
         Line::from(Span::raw(&message)),
         Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                " y ",
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" Yes  "),
-            Span::styled(
-                " n ",
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Red)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" No"),
-        ]),
+        confirm_keys(),
     ];
 
     let dialog = Paragraph::new(content)
         .block(
             Block::default()
-                .title(title)
+                .title(" Confirm Remove ")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Yellow)),
@@ -63,6 +48,224 @@ pub fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction
     frame.render_widget(dialog, dialog_area);
 }
 
+/// Diff preview for a pending change plan (cross-project or bulk writes)
+fn render_plan_dialog(frame: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(80, 70, area);
+
+    // Clear the background
+    frame.render_widget(Clear, dialog_area);
+
+    let mut content = Vec::new();
+
+    if let Some(ref change) = app.pending_change {
+        content.push(Line::from(Span::styled(
+            format!(
+                "{} {} in {} scope:",
+                if change.enabled { "Enable" } else { "Disable" },
+                change.plugin_id,
+                change.scope
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        content.push(Line::from(""));
+
+        for line in change.plan.unified_diff().lines() {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else if line.starts_with('+') {
+                Style::default().fg(Color::Green)
+            } else if line.starts_with('-') {
+                Style::default().fg(Color::Red)
+            } else if line.starts_with("@@") {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            content.push(Line::from(Span::styled(line.to_string(), style)));
+        }
+
+        if change.plan.is_empty() {
+            content.push(Line::from(Span::styled(
+                "No changes",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    content.push(Line::from(""));
+    content.push(confirm_keys());
+
+    let dialog = Paragraph::new(content).block(
+        Block::default()
+            .title(" Confirm Changes ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// Extra confirmation before enabling a plugin that runs hooks
+fn render_hooks_dialog(frame: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(80, 70, area);
+
+    // Clear the background
+    frame.render_widget(Clear, dialog_area);
+
+    let mut content = Vec::new();
+
+    if let Some(plugin) = app.selected_plugin() {
+        let hooks = &plugin.contents.hooks;
+        content.push(Line::from(Span::styled(
+            format!(
+                "{} runs {} hook{} as shell commands on your machine:",
+                plugin.id,
+                hooks.len(),
+                if hooks.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        content.push(Line::from(""));
+
+        for hook in hooks {
+            let matcher = hook
+                .matcher
+                .as_ref()
+                .map(|m| format!(" [{}]", m))
+                .unwrap_or_default();
+            content.push(Line::from(vec![
+                Span::styled(
+                    format!("{}{}", hook.event, matcher),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("  {}", hook.command.as_deref().unwrap_or(&hook.hook_type)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            content.extend(risk_lines(&hook.risks));
+        }
+
+        content.push(Line::from(""));
+        content.push(Line::from(Span::raw("Enable it anyway?")));
+    }
+
+    content.push(Line::from(""));
+    content.push(confirm_keys());
+
+    let border = if app
+        .selected_plugin()
+        .is_some_and(|p| p.contents.has_risky_hooks())
+    {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+
+    let dialog = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(" Plugin Hooks ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// Bisect wizard: the plugins enabled in the current step and the answer keys
+pub fn render_bisect_dialog(frame: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(70, 60, area);
+
+    // Clear the background
+    frame.render_widget(Clear, dialog_area);
+
+    let mut content = Vec::new();
+    if let Some(ref state) = app.bisect {
+        let testing = state.testing();
+        content.push(Line::from(Span::styled(
+            format!(
+                "Step {}: testing {} of {} suspects (about {} steps left)",
+                state.steps + 1,
+                testing.len(),
+                state.suspects.len(),
+                state.steps_left()
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        content.push(Line::from(""));
+        content.push(Line::from("Enabled in local scope:"));
+        for id in testing {
+            content.push(Line::from(Span::styled(
+                format!("  {}", id),
+                Style::default().fg(Color::Green),
+            )));
+        }
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(
+            "Restart Claude Code and try to reproduce the problem.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    content.push(Line::from(""));
+    let key = |k: &'static str, color: Color| {
+        Span::styled(
+            k,
+            Style::default()
+                .fg(Color::Black)
+                .bg(color)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    content.push(Line::from(vec![
+        key(" g ", Color::Green),
+        Span::raw(" Good  "),
+        key(" b ", Color::Red),
+        Span::raw(" Bad  "),
+        key(" r ", Color::Yellow),
+        Span::raw(" Reset  "),
+        key(" Esc ", Color::Cyan),
+        Span::raw(" Close"),
+    ]));
+
+    let dialog = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(" Bisect ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+fn confirm_keys() -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            " y ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" Yes  "),
+        Span::styled(
+            " n ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" No"),
+    ])
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::vertical([
         Constraint::Percentage((100 - percent_y) / 2),
@@ -28,21 +28,40 @@ pub fn render_help(frame: &mut Frame, area: Rect) {
             "Plugin Actions",
             vec![
                 ("e", "Enable plugin"),
+                ("t", "Enable for a while (e.g. 2h)"),
                 ("d", "Disable plugin"),
                 ("Space", "Toggle enable/disable"),
-                ("u", "Toggle auto-update"),
                 ("x", "Remove plugin"),
+                ("B", "Bisect to find a problem plugin"),
                 ("U", "Update plugin"),
             ],
         ),
         (
             "Filtering",
             vec![
-                ("s", "Cycle scope filter (All/User/Local)"),
+                ("s", "Cycle scope filter (All/User/Project/Local/Dev)"),
                 ("/", "Start search"),
                 ("Esc", "Clear search / Exit mode"),
             ],
         ),
+        (
+            "MCP Servers Tab",
+            vec![
+                (
+                    "Tab",
+                    "Switch between Plugins, MCP Servers and Marketplaces",
+                ),
+                ("Space", "Approve/deny a project .mcp.json server"),
+            ],
+        ),
+        (
+            "Marketplaces Tab",
+            vec![
+                ("u / Space", "Toggle auto-update"),
+                ("a", "Add a marketplace the project declares"),
+                ("Enter", "View catalog"),
+            ],
+        ),
         (
             "General",
             vec![("?", "Toggle help"), ("r", "Reload plugins"), ("q", "Quit")],
@@ -0,0 +1,283 @@
+use crate::app::App;
+use crate::plugin::{format_timestamp, short_commit, MarketplaceSummary, PendingMarketplace};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render_marketplace_list(frame: &mut Frame, app: &App, area: Rect) {
+    let mut items: Vec<ListItem> = app
+        .marketplaces
+        .iter()
+        .map(|marketplace| {
+            let auto_update = if marketplace.entry.auto_update_enabled() {
+                Span::styled("[A] ", Style::default().fg(Color::Green))
+            } else {
+                Span::raw("    ")
+            };
+
+            ListItem::new(Line::from(vec![
+                auto_update,
+                Span::styled(&marketplace.name, Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(
+                        " {} {}/{}",
+                        marketplace.entry.source.kind(),
+                        marketplace.installed.len(),
+                        marketplace.available_count()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    // Declared in the project's extraKnownMarketplaces but not added yet
+    items.extend(app.pending_marketplaces.iter().map(|pending| {
+        ListItem::new(Line::from(vec![
+            Span::styled("[?] ", Style::default().fg(Color::Yellow)),
+            Span::styled(&pending.name, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(" {} pending", pending.source.kind()),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]))
+    }));
+
+    let title = if app.pending_marketplaces.is_empty() {
+        format!(" Marketplaces ({}) ", app.marketplaces.len())
+    } else {
+        format!(
+            " Marketplaces ({}, {} pending) ",
+            app.marketplaces.len(),
+            app.pending_marketplaces.len()
+        )
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.marketplace_selected));
+
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_marketplace_details(frame: &mut Frame, app: &App, area: Rect) {
+    let content = if let Some(marketplace) = app.selected_marketplace() {
+        let mut lines = summary_lines(marketplace);
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Press u to toggle auto-update, Enter for the catalog",
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines
+    } else if let Some(pending) = app.selected_pending_marketplace() {
+        pending_lines(pending)
+    } else {
+        vec![Line::from(Span::styled(
+            "No marketplaces. Add one with: ccpm marketplace add <source>",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    };
+
+    let details = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(" Details ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(details, area);
+}
+
+pub fn render_marketplace_modal(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(marketplace) = app.selected_marketplace() else {
+        return;
+    };
+    let modal_area = centered_rect(75, 80, area);
+
+    // Clear the background
+    frame.render_widget(Clear, modal_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            &marketplace.name,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    lines.extend(summary_lines(marketplace));
+    lines.push(Line::from(""));
+
+    match marketplace.catalog {
+        Some(ref catalog) => {
+            if let Some(ref owner) = catalog.owner {
+                lines.push(Line::from(vec![
+                    Span::styled("Owner: ", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(&owner.name),
+                ]));
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                format!("Catalog ({})", catalog.plugins.len()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for plugin in &catalog.plugins {
+                let mut spans = if marketplace.is_installed(&plugin.name) {
+                    vec![
+                        Span::styled("  [I] ", Style::default().fg(Color::Green)),
+                        Span::styled(&plugin.name, Style::default().fg(Color::White)),
+                    ]
+                } else {
+                    vec![
+                        Span::raw("      "),
+                        Span::styled(&plugin.name, Style::default().fg(Color::DarkGray)),
+                    ]
+                };
+                if let Some(ref version) = plugin.version {
+                    spans.push(Span::styled(
+                        format!(" v{}", version),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if let Some(ref description) = plugin.description {
+                    spans.push(Span::styled(
+                        format!(" - {}", description),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            "No .claude-plugin/marketplace.json in the checkout",
+            Style::default().fg(Color::Red),
+        ))),
+    }
+
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Marketplace ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(modal, modal_area);
+}
+
+/// Source, checkout and auto-update lines shared by the panel and the modal
+fn summary_lines(marketplace: &MarketplaceSummary) -> Vec<Line<'_>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let entry = &marketplace.entry;
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Name: ", bold),
+            Span::raw(&marketplace.name),
+        ]),
+        Line::from(vec![
+            Span::styled("Source: ", bold),
+            Span::raw(entry.source.to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("Location: ", bold),
+            Span::styled(
+                entry.install_location.display().to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+    ];
+    if let Some(ref head) = marketplace.head {
+        lines.push(Line::from(vec![
+            Span::styled("Commit: ", bold),
+            Span::raw(short_commit(head)),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled("Last updated: ", bold),
+        Span::raw(format_timestamp(&entry.last_updated)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Auto-update: ", bold),
+        if entry.auto_update_enabled() {
+            Span::styled("on", Style::default().fg(Color::Green))
+        } else {
+            Span::styled("off", Style::default().fg(Color::DarkGray))
+        },
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Plugins: ", bold),
+        Span::raw(format!(
+            "{} installed, {} available",
+            marketplace.installed.len(),
+            marketplace.available_count()
+        )),
+    ]));
+    lines
+}
+
+fn pending_lines(pending: &PendingMarketplace) -> Vec<Line<'_>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    vec![
+        Line::from(vec![Span::styled("Name: ", bold), Span::raw(&pending.name)]),
+        Line::from(vec![
+            Span::styled("Source: ", bold),
+            Span::raw(pending.source.to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("Declared in: ", bold),
+            Span::styled(
+                pending.declared_in.display().to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Status: ", bold),
+            Span::styled("pending", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "This project declares the marketplace in extraKnownMarketplaces, but it is not in known_marketplaces.json. Press a to add it.",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .split(r);
+
+    Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .split(popup_layout[1])[1]
+}
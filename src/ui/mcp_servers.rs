@@ -0,0 +1,160 @@
+use crate::app::App;
+use crate::plugin::{McpApproval, McpOrigin};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render_mcp_server_list(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .mcp_servers
+        .iter()
+        .map(|entry| {
+            // Origin indicator: [L]ocal, [P]roject, [U]ser or plugin [+]
+            let origin = match entry.origin {
+                McpOrigin::Local(_) => Span::styled("[L]", Style::default().fg(Color::Magenta)),
+                McpOrigin::Project(_) => Span::styled("[P]", Style::default().fg(Color::Cyan)),
+                McpOrigin::User(_) => Span::styled("[U]", Style::default().fg(Color::Blue)),
+                McpOrigin::Plugin(_) => Span::styled("[+]", Style::default().fg(Color::Green)),
+            };
+
+            let approval = match entry.approval {
+                McpApproval::NotRequired => Span::raw("     "),
+                McpApproval::Approved(_) => {
+                    Span::styled(" [✓] ", Style::default().fg(Color::Green))
+                }
+                McpApproval::Pending => Span::styled(" [?] ", Style::default().fg(Color::Yellow)),
+                McpApproval::Denied(_) => Span::styled(" [✗] ", Style::default().fg(Color::Red)),
+            };
+
+            let name_style = if entry.is_active() {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+
+            let source = match entry.origin {
+                McpOrigin::Plugin(ref id) => format!(" {}", id),
+                _ => format!(" {}", entry.origin.label()),
+            };
+
+            ListItem::new(Line::from(vec![
+                origin,
+                approval,
+                Span::styled(&entry.name, name_style),
+                Span::styled(source, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let title = format!(" MCP Servers ({}) ", app.mcp_servers.len());
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.mcp_selected));
+
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_mcp_server_details(frame: &mut Frame, app: &App, area: Rect) {
+    let content = if let Some(entry) = app.selected_mcp_server() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::from(vec![Span::styled("Name: ", bold), Span::raw(&entry.name)]),
+            Line::from(vec![
+                Span::styled(
+                    match entry.origin {
+                        McpOrigin::Plugin(_) => "Plugin: ",
+                        _ => "File: ",
+                    },
+                    bold,
+                ),
+                Span::raw(entry.origin.to_string()),
+                Span::styled(
+                    format!(" ({})", entry.origin.label()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Transport: ", bold),
+                Span::raw(entry.server.transport_name()),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    if entry.server.command.is_some() {
+                        "Command: "
+                    } else {
+                        "URL: "
+                    },
+                    bold,
+                ),
+                Span::styled(entry.server.target(), Style::default().fg(Color::DarkGray)),
+            ]),
+        ];
+
+        for (label, keys) in [
+            ("Env: ", entry.redacted_env()),
+            ("Headers: ", entry.redacted_headers()),
+        ] {
+            if !keys.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled(label, bold),
+                    Span::styled(keys.join(", "), Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+        }
+
+        let approval_style = match entry.approval {
+            McpApproval::NotRequired => Style::default().fg(Color::DarkGray),
+            McpApproval::Approved(_) => Style::default().fg(Color::Green),
+            McpApproval::Pending => Style::default().fg(Color::Yellow),
+            McpApproval::Denied(_) => Style::default().fg(Color::Red),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("Approval: ", bold),
+            Span::styled(entry.approval.to_string(), approval_style),
+        ]));
+
+        if entry.needs_approval() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Press Space to approve or deny in local settings",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        lines
+    } else {
+        vec![Line::from(Span::styled(
+            "No MCP servers in this project",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    };
+
+    let details = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(" Details ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(details, area);
+}
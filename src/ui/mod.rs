@@ -2,15 +2,22 @@ mod detail_modal;
 mod details;
 mod dialogs;
 mod help;
+mod marketplaces;
+mod mcp_servers;
 mod plugin_list;
 
 pub use detail_modal::render_detail_modal;
 pub use details::render_details;
-pub use dialogs::render_confirm_dialog;
+pub use dialogs::{render_bisect_dialog, render_confirm_dialog};
 pub use help::render_help;
+pub use marketplaces::{
+    render_marketplace_details, render_marketplace_list, render_marketplace_modal,
+};
+pub use mcp_servers::{render_mcp_server_details, render_mcp_server_list};
 pub use plugin_list::render_plugin_list;
 
-use crate::app::{App, AppMode};
+use crate::app::{App, AppMode, Tab};
+use crate::plugin::format_tokens;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -41,11 +48,21 @@ pub fn render(frame: &mut Frame, app: &App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(main_chunks[1]);
 
-    // Render plugin list
-    render_plugin_list(frame, app, content_chunks[0]);
-
-    // Render details panel
-    render_details(frame, app, content_chunks[1]);
+    // Render list and details panel for the active tab
+    match app.tab {
+        Tab::Plugins => {
+            render_plugin_list(frame, app, content_chunks[0]);
+            render_details(frame, app, content_chunks[1]);
+        }
+        Tab::McpServers => {
+            render_mcp_server_list(frame, app, content_chunks[0]);
+            render_mcp_server_details(frame, app, content_chunks[1]);
+        }
+        Tab::Marketplaces => {
+            render_marketplace_list(frame, app, content_chunks[0]);
+            render_marketplace_details(frame, app, content_chunks[1]);
+        }
+    }
 
     // Render footer/command bar
     render_footer(frame, app, main_chunks[2]);
@@ -55,6 +72,8 @@ pub fn render(frame: &mut Frame, app: &App) {
         AppMode::Help => render_help(frame, area),
         AppMode::Confirm(action) => render_confirm_dialog(frame, app, action, area),
         AppMode::DetailModal => render_detail_modal(frame, app, area),
+        AppMode::Bisect => render_bisect_dialog(frame, app, area),
+        AppMode::MarketplaceModal => render_marketplace_modal(frame, app, area),
         _ => {}
     }
 }
@@ -74,7 +93,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         })
         .unwrap_or_else(|_| "unknown".to_string());
 
-    let title = vec![
+    let mut title = vec![
         Span::styled(
             " CCPM ",
             Style::default()
@@ -82,6 +101,21 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw("│ "),
+    ];
+    for tab in Tab::ALL {
+        let style = if tab == app.tab {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        title.push(Span::styled(format!(" {} ", tab.label()), style));
+        title.push(Span::raw(" "));
+    }
+    title.extend([
+        Span::raw("│ "),
         Span::styled(
             format!("CWD: {} ", cwd_display),
             Style::default().fg(Color::DarkGray),
@@ -96,7 +130,18 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
             format!("{}/{} enabled ", enabled, total),
             Style::default().fg(Color::Green),
         ),
-    ];
+        Span::styled(
+            format!("~{} tokens ", format_tokens(app.context_cost().tokens())),
+            Style::default().fg(Color::Blue),
+        ),
+    ]);
+    if let Some(bisect) = &app.bisect {
+        title.push(Span::raw("│ "));
+        title.push(Span::styled(
+            format!("Bisect: step {} ", bisect.steps + 1),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
 
     // Add search indicator if in search mode
     let title = if app.mode == AppMode::Search || !app.search_query.is_empty() {
@@ -125,7 +170,26 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     let commands = match app.mode {
+        AppMode::Normal if app.tab == Tab::McpServers => vec![
+            ("j/k", "navigate"),
+            ("Space", "approve/deny"),
+            ("Tab", "marketplaces"),
+            ("r", "reload"),
+            ("?", "help"),
+            ("q", "quit"),
+        ],
+        AppMode::Normal if app.tab == Tab::Marketplaces => vec![
+            ("j/k", "navigate"),
+            ("Enter", "details"),
+            ("u", "auto-update"),
+            ("a", "add pending"),
+            ("Tab", "plugins"),
+            ("r", "reload"),
+            ("?", "help"),
+            ("q", "quit"),
+        ],
         AppMode::Normal => vec![
+            ("Tab", "MCP servers"),
             ("j/k", "navigate"),
             ("Enter", "details"),
             ("e", "enable"),
@@ -136,9 +200,25 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
             ("q", "quit"),
         ],
         AppMode::Search => vec![("Enter/Esc", "exit search"), ("Type", "filter")],
+        AppMode::EnableFor => vec![
+            ("Enter", "enable"),
+            ("Esc", "cancel"),
+            ("Type", "45m, 2h, 1d"),
+        ],
+        AppMode::Bisect => vec![
+            ("g", "good"),
+            ("b", "bad"),
+            ("r", "reset"),
+            ("Esc", "close"),
+        ],
         AppMode::Help => vec![("Esc/?", "close help")],
         AppMode::Confirm(_) => vec![("y", "confirm"), ("n/Esc", "cancel")],
-        AppMode::DetailModal => vec![("Esc/Enter", "close"), ("Space", "toggle")],
+        AppMode::DetailModal => vec![
+            ("Esc/Enter", "close"),
+            ("Space", "toggle"),
+            ("m", "check MCP"),
+        ],
+        AppMode::MarketplaceModal => vec![("Esc/Enter", "close"), ("u", "auto-update")],
     };
 
     let mut spans: Vec<Span> = Vec::new();
@@ -156,8 +236,20 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
         spans.push(Span::raw(format!(" {} ", desc)));
     }
 
-    // Add status message if present
-    let footer_content = if let Some(ref msg) = app.message {
+    // Duration prompt, or the status message if present
+    let footer_content = if app.mode == AppMode::EnableFor {
+        let name = app.selected_plugin().map(|p| p.name.as_str()).unwrap_or("");
+        vec![
+            Line::from(spans),
+            Line::from(vec![
+                Span::styled(
+                    format!("Enable {} for: {}", name, app.duration_input),
+                    Style::default().fg(Color::Magenta),
+                ),
+                Span::styled("_", Style::default().fg(Color::Magenta)),
+            ]),
+        ]
+    } else if let Some(ref msg) = app.message {
         let color = if msg.is_error {
             Color::Red
         } else {
@@ -9,16 +9,18 @@ use ratatui::{
 };
 
 pub fn render_plugin_list(frame: &mut Frame, app: &App, area: Rect) {
+    let now = chrono::Utc::now();
     let items: Vec<ListItem> = app
         .filtered_plugins
         .iter()
         .map(|&idx| {
             let plugin = &app.plugins[idx];
 
-            // Scope indicator: [U], [P], [P*], [L], or [L*] for different project
+            // Scope indicator: [U], [P], [P*], [L], [L*] for different project, or [D]ev
             let scope_indicator = Span::styled(
                 plugin.scope_indicator(),
                 match (plugin.install_scope, plugin.is_current_project) {
+                    _ if plugin.is_dev => Style::default().fg(Color::Green),
                     (Scope::User, _) => Style::default().fg(Color::Blue),
                     (Scope::Project, true) => Style::default().fg(Color::Cyan),
                     (Scope::Project, false) => Style::default().fg(Color::Yellow), // Different project
@@ -46,12 +48,44 @@ pub fn render_plugin_list(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::DarkGray),
             );
 
-            ListItem::new(Line::from(vec![
-                scope_indicator,
-                status_indicator,
-                name,
-                marketplace,
-            ]))
+            let mut spans = vec![scope_indicator, status_indicator, name, marketplace];
+
+            // Hook badge: red when a hook matches a risky pattern
+            if plugin.contents.has_risky_hooks() {
+                spans.push(Span::styled(" [H!]", Style::default().fg(Color::Red)));
+            } else if !plugin.contents.hooks.is_empty() {
+                spans.push(Span::styled(" [H]", Style::default().fg(Color::DarkGray)));
+            }
+
+            // Countdown badge: enabled with `--for`, reverts when it reaches zero
+            if let Some(expiry) = app.expiry_for(&plugin.id) {
+                spans.push(Span::styled(
+                    format!(" [{}]", expiry.remaining(now)),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+
+            // Update badge: the marketplace checkout has a newer version or commit;
+            // pinned plugins show the pin instead
+            if let Some(update) = app.update_for(&plugin.id) {
+                if update.pin.is_some() {
+                    spans.push(Span::styled(" [P]", Style::default().fg(Color::Magenta)));
+                } else if update.can_update() {
+                    spans.push(Span::styled(" [U]", Style::default().fg(Color::Cyan)));
+                }
+            }
+
+            // Error badge: the dev plugin failed validation on the last reload
+            if plugin.error_count() > 0 {
+                spans.push(Span::styled(" [E]", Style::default().fg(Color::Red)));
+            }
+
+            // Warning badge: shares a command/agent/skill/MCP server name with another active plugin
+            if app.collisions_for(&plugin.id).next().is_some() {
+                spans.push(Span::styled(" [!]", Style::default().fg(Color::Yellow)));
+            }
+
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -0,0 +1,52 @@
+#!/bin/sh
+# Minimal MCP stdio server for the `ccpm mcp check` integration tests.
+#
+# Answers `initialize` and `tools/list` with newline-delimited JSON-RPC.
+# `--fail` exits at once with an error on stderr, `--silent` never answers, and
+# `--require-env NAME` fails like `--fail` when NAME is unset or empty.
+
+silent=
+while [ $# -gt 0 ]; do
+    case "$1" in
+        --fail)
+            echo "fake-mcp: startup failed" >&2
+            exit 2
+            ;;
+        --require-env)
+            eval "value=\${$2:-}"
+            if [ -z "$value" ]; then
+                echo "fake-mcp: $2 is not set" >&2
+                exit 2
+            fi
+            shift
+            ;;
+        --silent)
+            silent=1
+            ;;
+    esac
+    shift
+done
+
+# ccpm sends compact JSON, one message per line
+while IFS= read -r line; do
+    # Notifications carry no id and get no response
+    id=$(printf '%s\n' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+    if [ -z "$id" ] || [ -n "$silent" ]; then
+        continue
+    fi
+
+    case "$line" in
+        *'"method":"initialize"'*)
+            version=$(printf '%s\n' "$line" | sed -n 's/.*"protocolVersion":"\([^"]*\)".*/\1/p')
+            # Servers may log before answering; clients must skip it
+            printf '%s\n' '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info","data":"starting"}}'
+            printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"%s","capabilities":{"tools":{}},"serverInfo":{"name":"fake-mcp","version":"0.3.1"}}}\n' "$id" "$version"
+            ;;
+        *'"method":"tools/list"'*)
+            printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object"}},{"name":"add","inputSchema":{"type":"object"}}]}}\n' "$id"
+            ;;
+        *)
+            printf '{"jsonrpc":"2.0","id":%s,"error":{"code":-32601,"message":"Method not found"}}\n' "$id"
+            ;;
+    esac
+done